@@ -0,0 +1,742 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::OsString,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    mem_fs::MemFile, utils::PathUtil, Error, FileType, LogixVfs, LogixVfsDirEntry, LogixVfsMut,
+    MemFs, Metadata,
+};
+
+/// A change recorded by [CowFs] that has not been committed yet
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Change {
+    /// The file or directory exists in the lower file system but was removed
+    Removed(PathBuf),
+    /// A directory was created
+    CreatedDir(PathBuf),
+    /// A file was created or replaced
+    Written(PathBuf),
+}
+
+/// A copy-on-write file system. Reads are served from `lower` unless the
+/// path has been modified, while all modifications are kept in an in-memory
+/// upper layer until they are either committed to `lower` or discarded.
+///
+/// Removed paths are recorded as whiteouts, which hide the path and
+/// everything below it in the lower file system.
+///
+/// Renaming a path that exists in the lower file system copies it into the
+/// upper layer, for a directory this reads the whole subtree into memory.
+/// Symbolic links are copied as the file or directory they point to, as
+/// [LogixVfsMut] has no way to create links when committing.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct CowFs<L: LogixVfs> {
+    lower: L,
+    upper: MemFs,
+    whiteouts: BTreeSet<PathBuf>,
+}
+
+impl<L: LogixVfs> CowFs<L> {
+    pub fn new(lower: L) -> Self {
+        Self {
+            lower,
+            upper: MemFs::default(),
+            whiteouts: BTreeSet::new(),
+        }
+    }
+
+    pub fn lower(&self) -> &L {
+        &self.lower
+    }
+
+    /// The in-memory layer holding the modified files
+    pub fn upper(&self) -> &MemFs {
+        &self.upper
+    }
+
+    /// Drop all pending changes, returning the lower file system
+    pub fn into_lower(self) -> L {
+        self.lower
+    }
+
+    /// Returns true if there is anything to commit
+    pub fn has_changes(&self) -> bool {
+        !self.whiteouts.is_empty() || self.upper != MemFs::default()
+    }
+
+    /// List the pending changes, removals first, suitable for showing a
+    /// preview before calling [CowFs::commit]
+    pub fn changes(&self) -> Result<Vec<Change>, Error> {
+        let mut ret = Vec::new();
+
+        for path in &self.whiteouts {
+            if self.lower.metadata(path).is_ok() {
+                ret.push(Change::Removed(path.clone()));
+            }
+        }
+
+        self.visit_upper("/".as_ref(), &mut |path, is_dir| {
+            if !is_dir {
+                ret.push(Change::Written(path.to_path_buf()));
+            } else if self.is_whited_out(path) || self.lower.metadata(path).is_err() {
+                ret.push(Change::CreatedDir(path.to_path_buf()));
+            }
+            Ok(())
+        })?;
+
+        Ok(ret)
+    }
+
+    /// Forget all pending changes
+    pub fn discard(&mut self) {
+        self.upper = MemFs::default();
+        self.whiteouts.clear();
+    }
+
+    /// Apply all pending changes to the lower file system. Removals are
+    /// applied first, then directories and files are written in order. If
+    /// this fails, the pending changes are kept so the commit can be retried.
+    pub fn commit(&mut self) -> Result<(), Error>
+    where
+        L: LogixVfsMut,
+    {
+        for path in &self.whiteouts {
+            match self.lower.metadata(path) {
+                Ok(meta) if meta.is_dir() => self.lower.remove_dir_all(path)?,
+                Ok(_) => self.lower.remove_file(path)?,
+                Err(Error::NotFound { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let lower = &mut self.lower;
+        let upper = &self.upper;
+        visit(upper, "/".as_ref(), &mut |path, is_dir| {
+            if is_dir {
+                lower.create_dir_all(path)
+            } else {
+                let data = upper.open_file(path)?.into_inner();
+                lower.write_file(path, data.as_ref())
+            }
+        })?;
+
+        self.discard();
+        Ok(())
+    }
+
+    fn resolve_path(&self, path: &Path) -> Result<PathBuf, Error> {
+        PathUtil {
+            root: "/".as_ref(),
+            cur_dir: "/".as_ref(),
+        }
+        .resolve_path(false, path)
+    }
+
+    fn is_whited_out(&self, full_path: &Path) -> bool {
+        full_path
+            .ancestors()
+            .any(|path| self.whiteouts.contains(path))
+    }
+
+    fn visit_upper(
+        &self,
+        path: &Path,
+        f: &mut impl FnMut(&Path, bool) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        visit(&self.upper, path, f)
+    }
+
+    /// Look up `full_path` in the upper layer, falling back to the lower
+    /// layer unless it has been removed
+    fn lookup<T>(
+        &self,
+        full_path: &Path,
+        upper: impl FnOnce(&MemFs) -> Result<T, Error>,
+        lower: impl FnOnce(&L) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        match upper(&self.upper) {
+            Err(Error::NotFound { .. }) if !self.is_whited_out(full_path) => lower(&self.lower),
+            Err(Error::NotFound { .. }) => Err(Error::not_found(full_path)),
+            res => res,
+        }
+    }
+
+    fn merged_metadata(&self, full_path: &Path) -> Result<Metadata, Error> {
+        self.lookup(
+            full_path,
+            |upper| upper.metadata(full_path),
+            |lower| lower.metadata(full_path),
+        )
+    }
+
+    /// Make sure the parent of `full_path` is a directory, and create it in
+    /// the upper layer so files can be written to it
+    fn prepare_parent(&mut self, full_path: &Path) -> Result<(), Error> {
+        let Some(parent) = full_path.parent() else {
+            return Err(Error::InvalidPath {
+                path: full_path.to_path_buf(),
+                source: None,
+            });
+        };
+        match self.merged_metadata(parent) {
+            Ok(meta) if meta.is_dir() => self.upper.create_dir_all(parent),
+            Ok(_) => Err(Error::NotADirectory {
+                path: parent.to_path_buf(),
+                source: None,
+            }),
+            Err(Error::NotFound { .. }) => Err(Error::not_found(full_path)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Hide `full_path` in the lower layer if it exists there
+    fn white_out(&mut self, full_path: &Path) {
+        if !self.is_whited_out(full_path) && self.lower.metadata(full_path).is_ok() {
+            self.whiteouts.insert(full_path.to_path_buf());
+        }
+    }
+
+    /// Copy `from` and everything below it into the upper layer at `to`,
+    /// following symbolic links
+    fn copy_up(&mut self, from: &Path, to: &Path) -> Result<(), Error> {
+        if self.merged_metadata(from)?.is_dir() {
+            self.upper.create_dir_all(to)?;
+            let children: Vec<_> = self
+                .read_dir(from)?
+                .map(|entry| entry.map(|e| e.path))
+                .collect::<Result<_, _>>()?;
+            for child in children {
+                let name = child.file_name().expect("Entries always have a name");
+                self.copy_up(&child, &to.join(name))?;
+            }
+            Ok(())
+        } else {
+            let data = self.read(from)?;
+            self.upper.write_file(to, &data)
+        }
+    }
+}
+
+fn visit(
+    fs: &MemFs,
+    path: &Path,
+    f: &mut impl FnMut(&Path, bool) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let entries: Vec<_> = match fs.read_dir(path) {
+        Ok(it) => it.collect::<Result<_, _>>()?,
+        Err(Error::NotFound { .. }) => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for entry in entries {
+        f(entry.path(), entry.is_dir())?;
+        if entry.is_dir() {
+            visit(fs, entry.path(), f)?;
+        }
+    }
+    Ok(())
+}
+
+/// A file opened from either the lower or the upper layer
+#[derive(Debug)]
+pub enum File<F> {
+    Lower(F),
+    Upper(MemFile),
+}
+
+impl<F: Read> Read for File<F> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Lower(file) => file.read(buf),
+            Self::Upper(file) => file.read(buf),
+        }
+    }
+}
+
+impl<F: Seek> Seek for File<F> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        match self {
+            Self::Lower(file) => file.seek(pos),
+            Self::Upper(file) => file.seek(pos),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct DirEntry {
+    path: PathBuf,
+    ty: FileType,
+}
+
+impl DirEntry {
+    fn new(path: PathBuf, entry: &impl LogixVfsDirEntry) -> Self {
+        Self {
+            path,
+            ty: FileType::of(entry),
+        }
+    }
+}
+
+impl LogixVfsDirEntry for DirEntry {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn is_dir(&self) -> bool {
+        self.ty == FileType::Dir
+    }
+
+    fn is_file(&self) -> bool {
+        self.ty == FileType::File
+    }
+
+    fn is_symlink(&self) -> bool {
+        self.ty == FileType::Symlink
+    }
+}
+
+pub type ReadDir = std::vec::IntoIter<Result<DirEntry, Error>>;
+
+impl<L: LogixVfs> LogixVfs for CowFs<L> {
+    type RoFile = File<L::RoFile>;
+    type DirEntry = DirEntry;
+    type ReadDir = ReadDir;
+
+    fn canonicalize_path(&self, path: &Path) -> Result<PathBuf, Error> {
+        self.resolve_path(path)
+    }
+
+    fn open_file(&self, path: &Path) -> Result<Self::RoFile, Error> {
+        let full_path = self.resolve_path(path)?;
+        self.lookup(
+            &full_path,
+            |upper| upper.open_file(&full_path).map(File::Upper),
+            |lower| lower.open_file(&full_path).map(File::Lower),
+        )
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<Self::ReadDir, Error> {
+        let full_path = self.resolve_path(path)?;
+
+        let upper = match self.upper.read_dir(&full_path) {
+            Ok(it) => Some(it),
+            Err(Error::NotFound { .. }) => None,
+            Err(e) => return Err(e),
+        };
+        let lower = if self.is_whited_out(&full_path) {
+            None
+        } else {
+            match self.lower.read_dir(&full_path) {
+                Ok(it) => Some(it),
+                Err(Error::NotFound { .. }) => None,
+                // NOTE(2026.10): A directory in the upper layer replaces a file with the same path below it
+                Err(Error::NotADirectory { .. }) if upper.is_some() => None,
+                Err(e) => return Err(e),
+            }
+        };
+
+        if upper.is_none() && lower.is_none() {
+            return Err(Error::not_found(path));
+        }
+
+        let mut entries = BTreeMap::<OsString, _>::new();
+        let mut errors = Vec::new();
+
+        for entry in upper.into_iter().flatten() {
+            match entry {
+                Ok(entry) => {
+                    if let Some(name) = entry.path().file_name() {
+                        let path = full_path.join(name);
+                        entries.insert(name.to_owned(), DirEntry::new(path, &entry));
+                    }
+                }
+                Err(e) => errors.push(Err(e)),
+            }
+        }
+
+        for entry in lower.into_iter().flatten() {
+            match entry {
+                Ok(entry) => {
+                    let Some(name) = entry.path().file_name() else {
+                        continue;
+                    };
+                    let path = full_path.join(name);
+                    if !entries.contains_key(name) && !self.whiteouts.contains(&path) {
+                        entries.insert(name.to_owned(), DirEntry::new(path, &entry));
+                    }
+                }
+                Err(e) => errors.push(Err(e)),
+            }
+        }
+
+        Ok(entries
+            .into_values()
+            .map(Ok)
+            .chain(errors)
+            .collect::<Vec<_>>()
+            .into_iter())
+    }
+
+    fn metadata(&self, path: &Path) -> Result<Metadata, Error> {
+        self.merged_metadata(&self.resolve_path(path)?)
+    }
+
+    fn read_link(&self, path: &Path) -> Result<PathBuf, Error> {
+        let full_path = self.resolve_path(path)?;
+        self.lookup(
+            &full_path,
+            |upper| upper.read_link(&full_path),
+            |lower| lower.read_link(&full_path),
+        )
+    }
+}
+
+impl<L: LogixVfs> LogixVfsMut for CowFs<L> {
+    fn write_file(&mut self, path: &Path, data: &[u8]) -> Result<(), Error> {
+        let full_path = self.resolve_path(path)?;
+        if self.merged_metadata(&full_path).is_ok_and(|m| m.is_dir()) {
+            return Err(Error::IsADirectory {
+                path: path.to_path_buf(),
+                source: None,
+            });
+        }
+        self.prepare_parent(&full_path)?;
+        self.upper.write_file(&full_path, data)
+    }
+
+    fn append_file(&mut self, path: &Path, data: &[u8]) -> Result<(), Error> {
+        let full_path = self.resolve_path(path)?;
+        match self.merged_metadata(&full_path) {
+            Ok(meta) if meta.is_dir() => Err(Error::IsADirectory {
+                path: path.to_path_buf(),
+                source: None,
+            }),
+            Ok(_) => {
+                let mut content = self.read(&full_path)?;
+                content.extend_from_slice(data);
+                self.prepare_parent(&full_path)?;
+                self.upper.write_file(&full_path, &content)
+            }
+            Err(Error::NotFound { .. }) => {
+                self.prepare_parent(&full_path)?;
+                self.upper.write_file(&full_path, data)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn create_dir(&mut self, path: &Path) -> Result<(), Error> {
+        let full_path = self.resolve_path(path)?;
+        match self.merged_metadata(&full_path) {
+            Ok(_) => Err(Error::AlreadyExists {
+                path: path.to_path_buf(),
+                source: None,
+            }),
+            Err(Error::NotFound { .. }) => {
+                self.prepare_parent(&full_path)?;
+                self.upper.create_dir(&full_path)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn create_dir_all(&mut self, path: &Path) -> Result<(), Error> {
+        let full_path = self.resolve_path(path)?;
+        for dir in full_path.ancestors() {
+            match self.merged_metadata(dir) {
+                Ok(meta) if meta.is_dir() => break,
+                Ok(_) if dir == full_path => {
+                    return Err(Error::AlreadyExists {
+                        path: path.to_path_buf(),
+                        source: None,
+                    })
+                }
+                Ok(_) => {
+                    return Err(Error::NotADirectory {
+                        path: dir.to_path_buf(),
+                        source: None,
+                    })
+                }
+                Err(Error::NotFound { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.upper.create_dir_all(&full_path)
+    }
+
+    fn remove_file(&mut self, path: &Path) -> Result<(), Error> {
+        let full_path = self.resolve_path(path)?;
+        if self.merged_metadata(&full_path)?.is_dir() {
+            return Err(Error::IsADirectory {
+                path: path.to_path_buf(),
+                source: None,
+            });
+        }
+        match self.upper.remove_file(&full_path) {
+            Ok(()) | Err(Error::NotFound { .. }) => {}
+            Err(e) => return Err(e),
+        }
+        self.white_out(&full_path);
+        Ok(())
+    }
+
+    fn remove_dir(&mut self, path: &Path) -> Result<(), Error> {
+        let full_path = self.resolve_path(path)?;
+        if !self.merged_metadata(&full_path)?.is_dir() {
+            return Err(Error::NotADirectory {
+                path: path.to_path_buf(),
+                source: None,
+            });
+        }
+        if self.read_dir(&full_path)?.next().is_some() {
+            return Err(Error::DirectoryNotEmpty {
+                path: path.to_path_buf(),
+                source: None,
+            });
+        }
+        self.remove_dir_all(&full_path)
+    }
+
+    fn remove_dir_all(&mut self, path: &Path) -> Result<(), Error> {
+        let full_path = self.resolve_path(path)?;
+        if !self.merged_metadata(&full_path)?.is_dir() {
+            return Err(Error::NotADirectory {
+                path: path.to_path_buf(),
+                source: None,
+            });
+        }
+        match self.upper.remove_dir_all(&full_path) {
+            Ok(()) | Err(Error::NotFound { .. }) => {}
+            Err(e) => return Err(e),
+        }
+        self.white_out(&full_path);
+        Ok(())
+    }
+
+    fn rename(&mut self, from: &Path, to: &Path) -> Result<(), Error> {
+        let full_from = self.resolve_path(from)?;
+        let full_to = self.resolve_path(to)?;
+
+        let from_is_dir = self.merged_metadata(&full_from)?.is_dir();
+        if full_from == full_to {
+            return Ok(());
+        }
+        if from_is_dir && full_to.starts_with(&full_from) {
+            return Err(Error::InvalidPath {
+                path: to.to_path_buf(),
+                source: None,
+            });
+        }
+
+        match self.merged_metadata(&full_to) {
+            Ok(meta) if meta.is_dir() => {
+                if !from_is_dir {
+                    return Err(Error::IsADirectory {
+                        path: to.to_path_buf(),
+                        source: None,
+                    });
+                }
+                self.remove_dir(&full_to)?;
+            }
+            Ok(_) => {
+                if from_is_dir {
+                    return Err(Error::NotADirectory {
+                        path: to.to_path_buf(),
+                        source: None,
+                    });
+                }
+                self.remove_file(&full_to)?;
+            }
+            Err(Error::NotFound { .. }) => {}
+            Err(e) => return Err(e),
+        }
+
+        self.prepare_parent(&full_to)?;
+        self.copy_up(&full_from, &full_to)?;
+
+        if from_is_dir {
+            self.remove_dir_all(&full_from)
+        } else {
+            self.remove_file(&full_from)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(fs: &impl LogixVfs, path: &str) -> Vec<PathBuf> {
+        let mut ret: Vec<_> = fs
+            .read_dir(path.as_ref())
+            .unwrap()
+            .map(|e| e.unwrap().path().to_path_buf())
+            .collect();
+        ret.sort();
+        ret
+    }
+
+    fn lower() -> MemFs {
+        let mut fs = MemFs::default();
+        fs.create_dir_all("/etc/conf.d".as_ref()).unwrap();
+        fs.write_file("/etc/main.toml".as_ref(), b"main").unwrap();
+        fs.write_file("/etc/conf.d/a.toml".as_ref(), b"a").unwrap();
+        fs.write_file("/etc/conf.d/b.toml".as_ref(), b"b").unwrap();
+        fs
+    }
+
+    #[test]
+    fn reads_and_writes() {
+        let mut fs = CowFs::new(lower());
+        assert!(!fs.has_changes());
+
+        fs.write_file("/etc/main.toml".as_ref(), b"edited").unwrap();
+        fs.append_file("/etc/conf.d/a.toml".as_ref(), b"+").unwrap();
+        fs.write_file("/etc/conf.d/c.toml".as_ref(), b"c").unwrap();
+        fs.remove_file("/etc/conf.d/b.toml".as_ref()).unwrap();
+
+        assert_eq!(
+            fs.read_to_string("/etc/main.toml".as_ref()).unwrap(),
+            "edited"
+        );
+        assert_eq!(
+            fs.read_to_string("/etc/conf.d/a.toml".as_ref()).unwrap(),
+            "a+"
+        );
+        assert_eq!(
+            fs.read_to_string("/etc/conf.d/b.toml".as_ref())
+                .unwrap_err(),
+            Error::not_found("/etc/conf.d/b.toml")
+        );
+        assert_eq!(
+            list(&fs, "/etc/conf.d"),
+            [
+                PathBuf::from("/etc/conf.d/a.toml"),
+                PathBuf::from("/etc/conf.d/c.toml")
+            ]
+        );
+        assert_eq!(
+            fs.lower()
+                .read_to_string("/etc/main.toml".as_ref())
+                .unwrap(),
+            "main"
+        );
+
+        assert_eq!(
+            fs.changes().unwrap(),
+            [
+                Change::Removed("/etc/conf.d/b.toml".into()),
+                Change::Written("/etc/conf.d/a.toml".into()),
+                Change::Written("/etc/conf.d/c.toml".into()),
+                Change::Written("/etc/main.toml".into()),
+            ]
+        );
+
+        fs.discard();
+        assert!(!fs.has_changes());
+        assert_eq!(
+            fs.read_to_string("/etc/main.toml".as_ref()).unwrap(),
+            "main"
+        );
+        assert_eq!(list(&fs, "/etc/conf.d").len(), 2);
+    }
+
+    #[test]
+    fn removed_dirs_stay_hidden() {
+        let mut fs = CowFs::new(lower());
+
+        fs.remove_dir_all("/etc/conf.d".as_ref()).unwrap();
+        assert_eq!(list(&fs, "/etc"), [PathBuf::from("/etc/main.toml")]);
+
+        fs.create_dir("/etc/conf.d".as_ref()).unwrap();
+        fs.write_file("/etc/conf.d/new.toml".as_ref(), b"new")
+            .unwrap();
+        assert_eq!(
+            list(&fs, "/etc/conf.d"),
+            [PathBuf::from("/etc/conf.d/new.toml")]
+        );
+        assert!(fs.read_to_string("/etc/conf.d/a.toml".as_ref()).is_err());
+
+        fs.rename("/etc/conf.d".as_ref(), "/etc/moved".as_ref())
+            .unwrap();
+        fs.rename("/etc/main.toml".as_ref(), "/etc/moved/main.toml".as_ref())
+            .unwrap();
+        assert_eq!(list(&fs, "/etc"), [PathBuf::from("/etc/moved")]);
+        assert_eq!(
+            fs.read_to_string("/etc/moved/main.toml".as_ref()).unwrap(),
+            "main"
+        );
+
+        fs.commit().unwrap();
+        assert!(!fs.has_changes());
+
+        let lower = fs.into_lower();
+        assert_eq!(list(&lower, "/etc"), [PathBuf::from("/etc/moved")]);
+        assert_eq!(
+            list(&lower, "/etc/moved"),
+            [
+                PathBuf::from("/etc/moved/main.toml"),
+                PathBuf::from("/etc/moved/new.toml")
+            ]
+        );
+    }
+
+    #[test]
+    fn lower_errors() {
+        let mut lower = lower();
+        lower.set_permissions("/etc/conf.d", 0o700).unwrap();
+        lower.set_user(crate::mem_fs::User::new(1000, 1000));
+
+        let mut fs = CowFs::new(lower);
+        fs.write_file("/etc/conf.d/c.toml".as_ref(), b"c").unwrap();
+        assert_eq!(
+            fs.read_dir("/etc/conf.d".as_ref()).unwrap_err(),
+            Error::AccessDenied {
+                path: "/etc/conf.d".into(),
+                source: None
+            }
+        );
+
+        fs.remove_file("/etc/main.toml".as_ref()).unwrap();
+        fs.create_dir("/etc/main.toml".as_ref()).unwrap();
+        assert_eq!(list(&fs, "/etc/main.toml"), Vec::<PathBuf>::new());
+    }
+
+    #[test]
+    fn commit_to_disk() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir(tmp.path().join("conf.d")).unwrap();
+        std::fs::write(tmp.path().join("conf.d/old.toml"), "old").unwrap();
+        std::fs::write(tmp.path().join("keep.toml"), "keep").unwrap();
+
+        let mut fs = CowFs::new(crate::RelFs::new(tmp.path()));
+        fs.remove_file("conf.d/old.toml".as_ref()).unwrap();
+        fs.write_file("conf.d/new.toml".as_ref(), b"new").unwrap();
+        fs.create_dir_all("a/b".as_ref()).unwrap();
+
+        assert!(tmp.path().join("conf.d/old.toml").exists());
+        assert_eq!(
+            fs.changes().unwrap(),
+            [
+                Change::Removed("/conf.d/old.toml".into()),
+                Change::CreatedDir("/a".into()),
+                Change::CreatedDir("/a/b".into()),
+                Change::Written("/conf.d/new.toml".into()),
+            ]
+        );
+        fs.commit().unwrap();
+
+        assert!(!tmp.path().join("conf.d/old.toml").exists());
+        assert!(tmp.path().join("a/b").is_dir());
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join("conf.d/new.toml")).unwrap(),
+            "new"
+        );
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join("keep.toml")).unwrap(),
+            "keep"
+        );
+    }
+}
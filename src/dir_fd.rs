@@ -0,0 +1,330 @@
+use std::{
+    cmp::Ordering,
+    ffi::{OsStr, OsString},
+    fs::File,
+    hash::{Hash, Hasher},
+    io::{self, Write},
+    os::{
+        fd::{AsFd, BorrowedFd, OwnedFd},
+        unix::ffi::OsStringExt,
+    },
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use rustix::fs::{AtFlags, FileType, Mode, OFlags};
+
+use crate::utils::temp_name;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const SEARCH_DIR: OFlags = OFlags::PATH.union(OFlags::DIRECTORY);
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+const SEARCH_DIR: OFlags = OFlags::RDONLY.union(OFlags::DIRECTORY);
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const STAT_ONLY: OFlags = OFlags::PATH;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+const STAT_ONLY: OFlags = OFlags::RDONLY;
+
+/// An open directory containing `name`, returned by [DirFd::open_parent]
+pub(crate) struct Parent<'a> {
+    dir: Option<OwnedFd>,
+    root: BorrowedFd<'a>,
+    name: &'a OsStr,
+}
+
+impl Parent<'_> {
+    fn fd(&self) -> BorrowedFd<'_> {
+        self.dir.as_ref().map_or(self.root, |fd| fd.as_fd())
+    }
+}
+
+/// An open handle to a directory used as the anchor for all lookups, so that
+/// renaming or replacing the directory does not affect paths resolved later
+#[derive(Debug, Clone)]
+pub(crate) struct DirFd {
+    fd: Arc<OwnedFd>,
+    canonical_root: PathBuf,
+}
+
+impl DirFd {
+    pub fn open(root: &Path) -> io::Result<Self> {
+        let fd = rustix::fs::open(
+            root,
+            OFlags::RDONLY | OFlags::DIRECTORY | OFlags::CLOEXEC,
+            Mode::empty(),
+        )?;
+        Ok(Self {
+            fd: Arc::new(fd),
+            canonical_root: root.canonicalize()?,
+        })
+    }
+
+    /// The canonical path of the root at the time it was opened
+    pub fn canonical_root(&self) -> &Path {
+        &self.canonical_root
+    }
+
+    pub fn is_symlink(&self, rel: &Path) -> io::Result<bool> {
+        let stat = rustix::fs::statat(&*self.fd, dot_if_empty(rel), AtFlags::SYMLINK_NOFOLLOW)?;
+        Ok(FileType::from_raw_mode(stat.st_mode) == FileType::Symlink)
+    }
+
+    pub fn read_link(&self, rel: &Path) -> io::Result<PathBuf> {
+        let target = rustix::fs::readlinkat(&*self.fd, rel, Vec::new())?;
+        Ok(std::ffi::OsString::from_vec(target.into_bytes()).into())
+    }
+
+    /// Open the parent directory of `rel`. Unless `follow` is set, no
+    /// symbolic links are followed so a link swapped in after resolution
+    /// makes this fail rather than escape the root.
+    pub fn open_parent<'a>(&'a self, rel: &'a Path, follow: bool) -> io::Result<Parent<'a>> {
+        let (Some(parent), Some(name)) = (rel.parent(), rel.file_name()) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("The path {rel:?} does not have a parent directory"),
+            ));
+        };
+
+        let root = self.fd.as_fd();
+
+        if parent.as_os_str().is_empty() {
+            return Ok(Parent {
+                dir: None,
+                root,
+                name,
+            });
+        }
+
+        if follow {
+            return Ok(Parent {
+                dir: Some(rustix::fs::openat(
+                    root,
+                    parent,
+                    SEARCH_DIR | OFlags::CLOEXEC,
+                    Mode::empty(),
+                )?),
+                root,
+                name,
+            });
+        }
+
+        let mut cur: Option<OwnedFd> = None;
+
+        for component in parent.components() {
+            let Component::Normal(name) = component else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Internal error: path {rel:?} is not canonicalized"),
+                ));
+            };
+            let dir = cur.as_ref().map_or(root, |fd| fd.as_fd());
+            cur = Some(rustix::fs::openat(
+                dir,
+                name,
+                SEARCH_DIR | OFlags::NOFOLLOW | OFlags::CLOEXEC,
+                Mode::empty(),
+            )?);
+        }
+
+        Ok(Parent {
+            dir: cur,
+            root,
+            name,
+        })
+    }
+
+    fn open_at(parent: &Parent, flags: OFlags, mode: Mode, follow: bool) -> io::Result<OwnedFd> {
+        let nofollow = if follow {
+            OFlags::empty()
+        } else {
+            OFlags::NOFOLLOW
+        };
+        Ok(rustix::fs::openat(
+            parent.fd(),
+            parent.name,
+            flags | nofollow | OFlags::CLOEXEC,
+            mode,
+        )?)
+    }
+
+    /// Open `rel` below the root, see [DirFd::open_parent] for how links are
+    /// treated
+    pub fn open_beneath(&self, rel: &Path, flags: OFlags, follow: bool) -> io::Result<OwnedFd> {
+        if rel.as_os_str().is_empty() {
+            return Ok(rustix::fs::openat(
+                &*self.fd,
+                ".",
+                flags | OFlags::CLOEXEC,
+                Mode::empty(),
+            )?);
+        }
+        Self::open_at(
+            &self.open_parent(rel, follow)?,
+            flags,
+            Mode::empty(),
+            follow,
+        )
+    }
+
+    /// Replace the content of `rel` by writing to a temporary file and
+    /// renaming it over the original, keeping the permissions of the original
+    pub fn write_atomic(&self, rel: &Path, data: &[u8], follow: bool) -> io::Result<()> {
+        let parent = self.open_parent(rel, follow)?;
+        let tmp = temp_name(parent.name);
+        let mut file = File::from(rustix::fs::openat(
+            parent.fd(),
+            &tmp,
+            OFlags::WRONLY | OFlags::CREATE | OFlags::EXCL | OFlags::NOFOLLOW | OFlags::CLOEXEC,
+            Mode::from_raw_mode(0o666),
+        )?);
+
+        let res = (|| {
+            match rustix::fs::statat(parent.fd(), parent.name, AtFlags::empty()) {
+                Ok(stat) => rustix::fs::fchmod(&file, Mode::from_bits_truncate(stat.st_mode))?,
+                Err(rustix::io::Errno::NOENT) => {}
+                Err(e) => return Err(e.into()),
+            }
+            file.write_all(data)?;
+            rustix::fs::renameat(parent.fd(), &tmp, parent.fd(), parent.name)?;
+            Ok(())
+        })();
+
+        if res.is_err() {
+            let _ = rustix::fs::unlinkat(parent.fd(), &tmp, AtFlags::empty());
+        }
+        res
+    }
+
+    pub fn append(&self, rel: &Path, data: &[u8], follow: bool) -> io::Result<()> {
+        let parent = self.open_parent(rel, follow)?;
+        let fd = Self::open_at(
+            &parent,
+            OFlags::WRONLY | OFlags::APPEND | OFlags::CREATE,
+            Mode::from_raw_mode(0o666),
+            follow,
+        )?;
+        File::from(fd).write_all(data)
+    }
+
+    pub fn create_dir(&self, rel: &Path, follow: bool) -> io::Result<()> {
+        let parent = self.open_parent(rel, follow)?;
+        Ok(rustix::fs::mkdirat(
+            parent.fd(),
+            parent.name,
+            Mode::from_raw_mode(0o777),
+        )?)
+    }
+
+    pub fn remove_file(&self, rel: &Path, follow: bool) -> io::Result<()> {
+        let parent = self.open_parent(rel, follow)?;
+        Ok(rustix::fs::unlinkat(
+            parent.fd(),
+            parent.name,
+            AtFlags::empty(),
+        )?)
+    }
+
+    pub fn remove_dir(&self, rel: &Path, follow: bool) -> io::Result<()> {
+        let parent = self.open_parent(rel, follow)?;
+        Ok(rustix::fs::unlinkat(
+            parent.fd(),
+            parent.name,
+            AtFlags::REMOVEDIR,
+        )?)
+    }
+
+    pub fn remove_dir_all(&self, rel: &Path, follow: bool) -> io::Result<()> {
+        fn remove_all_at(dir: BorrowedFd, name: &OsStr) -> io::Result<()> {
+            let fd = match rustix::fs::openat(
+                dir,
+                name,
+                OFlags::RDONLY | OFlags::DIRECTORY | OFlags::NOFOLLOW | OFlags::CLOEXEC,
+                Mode::empty(),
+            ) {
+                Ok(fd) => fd,
+                // NOTE(2026.10): Not a directory, or a symbolic link, both are unlinked directly
+                Err(rustix::io::Errno::NOTDIR | rustix::io::Errno::LOOP) => {
+                    return Ok(rustix::fs::unlinkat(dir, name, AtFlags::empty())?)
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            let mut children = Vec::new();
+            for entry in rustix::fs::Dir::read_from(&fd)? {
+                let name = entry?.file_name().to_bytes().to_vec();
+                if name != b"." && name != b".." {
+                    children.push(OsString::from_vec(name));
+                }
+            }
+            for child in children {
+                remove_all_at(fd.as_fd(), &child)?;
+            }
+
+            Ok(rustix::fs::unlinkat(dir, name, AtFlags::REMOVEDIR)?)
+        }
+
+        let parent = self.open_parent(rel, follow)?;
+        let stat = rustix::fs::statat(parent.fd(), parent.name, AtFlags::SYMLINK_NOFOLLOW)?;
+        match FileType::from_raw_mode(stat.st_mode) {
+            FileType::Directory | FileType::Symlink => remove_all_at(parent.fd(), parent.name),
+            _ => Err(rustix::io::Errno::NOTDIR.into()),
+        }
+    }
+
+    pub fn rename(&self, from: &Path, to: &Path, follow: bool) -> io::Result<()> {
+        let from = self.open_parent(from, follow)?;
+        let to = self.open_parent(to, follow)?;
+        Ok(rustix::fs::renameat(
+            from.fd(),
+            from.name,
+            to.fd(),
+            to.name,
+        )?)
+    }
+
+    /// Query the metadata of `rel`, following the same rules as [DirFd::open_beneath]
+    pub fn metadata(&self, rel: &Path, follow: bool) -> io::Result<std::fs::Metadata> {
+        std::fs::File::from(self.open_beneath(rel, STAT_ONLY, follow)?).metadata()
+    }
+
+    /// Look up the type of `name` inside `dir` without following links
+    pub fn file_type_at(dir: BorrowedFd, name: &std::ffi::CStr) -> io::Result<FileType> {
+        let stat = rustix::fs::statat(dir, name, AtFlags::SYMLINK_NOFOLLOW)?;
+        Ok(FileType::from_raw_mode(stat.st_mode))
+    }
+}
+
+impl PartialEq for DirFd {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.fd, &other.fd)
+    }
+}
+
+impl Eq for DirFd {}
+
+impl PartialOrd for DirFd {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DirFd {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Arc::as_ptr(&self.fd).cmp(&Arc::as_ptr(&other.fd))
+    }
+}
+
+impl Hash for DirFd {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.fd).hash(state)
+    }
+}
+
+fn dot_if_empty(path: &Path) -> &Path {
+    if path.as_os_str().is_empty() {
+        ".".as_ref()
+    } else {
+        path
+    }
+}
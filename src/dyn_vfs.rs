@@ -0,0 +1,216 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{Error, FileType, LogixVfs, LogixVfsDirEntry, Metadata, ReadSeek};
+
+/// A file opened through a [DynLogixVfs]
+pub type DynFile = Box<dyn ReadSeek + Send>;
+
+/// A directory listing returned by a [DynLogixVfs]
+pub type DynReadDir = Box<dyn Iterator<Item = Result<DynDirEntry, Error>> + Send>;
+
+/// An object safe version of [LogixVfs], implemented for every [LogixVfs]
+/// so the backend can be picked at runtime and stored as
+/// `Arc<dyn DynLogixVfs>`, which in turn implements [LogixVfs].
+///
+/// The methods are prefixed with `dyn_` to not clash with the ones in
+/// [LogixVfs] when both traits are in scope.
+pub trait DynLogixVfs: std::fmt::Debug + Send + Sync {
+    fn dyn_canonicalize_path(&self, path: &Path) -> Result<PathBuf, Error>;
+    fn dyn_open_file(&self, path: &Path) -> Result<DynFile, Error>;
+    fn dyn_read_dir(&self, path: &Path) -> Result<DynReadDir, Error>;
+    fn dyn_metadata(&self, path: &Path) -> Result<Metadata, Error>;
+    fn dyn_read_link(&self, path: &Path) -> Result<PathBuf, Error>;
+    fn dyn_suggest_paths(&self, path: &Path) -> Vec<PathBuf>;
+}
+
+impl<T> DynLogixVfs for T
+where
+    T: LogixVfs,
+    T::RoFile: Send + 'static,
+    T::ReadDir: Send + 'static,
+{
+    fn dyn_canonicalize_path(&self, path: &Path) -> Result<PathBuf, Error> {
+        self.canonicalize_path(path)
+    }
+
+    fn dyn_open_file(&self, path: &Path) -> Result<DynFile, Error> {
+        Ok(Box::new(self.open_file(path)?))
+    }
+
+    fn dyn_read_dir(&self, path: &Path) -> Result<DynReadDir, Error> {
+        Ok(Box::new(
+            self.read_dir(path)?
+                .map(|entry| entry.map(|entry| DynDirEntry::new(&entry))),
+        ))
+    }
+
+    fn dyn_metadata(&self, path: &Path) -> Result<Metadata, Error> {
+        self.metadata(path)
+    }
+
+    fn dyn_read_link(&self, path: &Path) -> Result<PathBuf, Error> {
+        self.read_link(path)
+    }
+
+    fn dyn_suggest_paths(&self, path: &Path) -> Vec<PathBuf> {
+        self.suggest_paths(path)
+    }
+}
+
+/// A directory entry returned by a [DynLogixVfs]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct DynDirEntry {
+    path: PathBuf,
+    ty: FileType,
+}
+
+impl DynDirEntry {
+    fn new(entry: &impl LogixVfsDirEntry) -> Self {
+        Self {
+            path: entry.path().to_path_buf(),
+            ty: FileType::of(entry),
+        }
+    }
+
+    pub fn file_type(&self) -> FileType {
+        self.ty
+    }
+}
+
+impl LogixVfsDirEntry for DynDirEntry {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn is_dir(&self) -> bool {
+        self.ty == FileType::Dir
+    }
+
+    fn is_file(&self) -> bool {
+        self.ty == FileType::File
+    }
+
+    fn is_symlink(&self) -> bool {
+        self.ty == FileType::Symlink
+    }
+}
+
+impl LogixVfs for Arc<dyn DynLogixVfs> {
+    type RoFile = DynFile;
+    type DirEntry = DynDirEntry;
+    type ReadDir = DynReadDir;
+
+    fn canonicalize_path(&self, path: &Path) -> Result<PathBuf, Error> {
+        (**self).dyn_canonicalize_path(path)
+    }
+
+    fn open_file(&self, path: &Path) -> Result<Self::RoFile, Error> {
+        (**self).dyn_open_file(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<Self::ReadDir, Error> {
+        (**self).dyn_read_dir(path)
+    }
+
+    fn metadata(&self, path: &Path) -> Result<Metadata, Error> {
+        (**self).dyn_metadata(path)
+    }
+
+    fn read_link(&self, path: &Path) -> Result<PathBuf, Error> {
+        (**self).dyn_read_link(path)
+    }
+
+    fn suggest_paths(&self, path: &Path) -> Vec<PathBuf> {
+        (**self).dyn_suggest_paths(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LogixVfsMut, MemFs, MountFs, RelFs};
+
+    #[test]
+    fn mixed_backends() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("config.toml"), "disk").unwrap();
+
+        let mut mem = MemFs::default();
+        mem.write_file("/config.toml".as_ref(), b"memory").unwrap();
+
+        let mut fs = MountFs::<Arc<dyn DynLogixVfs>>::new();
+        fs.mount("/disk", Arc::new(RelFs::new(tmp.path()))).unwrap();
+        fs.mount("/mem", Arc::new(mem)).unwrap();
+
+        assert_eq!(
+            fs.read_to_string("/disk/config.toml".as_ref()).unwrap(),
+            "disk"
+        );
+        assert_eq!(
+            fs.read_to_string("/mem/config.toml".as_ref()).unwrap(),
+            "memory"
+        );
+        assert!(fs.metadata("/disk".as_ref()).unwrap().is_dir());
+        assert_eq!(
+            fs.metadata("/mem/missing.toml".as_ref()).unwrap_err(),
+            Error::not_found("/mem/missing.toml")
+        );
+
+        let entries: Vec<_> = fs
+            .read_dir("/disk".as_ref())
+            .unwrap()
+            .map(|e| {
+                let e = e.unwrap();
+                (e.path().to_path_buf(), e.is_file())
+            })
+            .collect();
+        assert_eq!(entries, [("/disk/config.toml".into(), true)]);
+    }
+
+    #[test]
+    fn picked_at_runtime() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("file"), "disk").unwrap();
+
+        for in_memory in [false, true] {
+            let fs: Arc<dyn DynLogixVfs> = if in_memory {
+                let mut fs = MemFs::default();
+                fs.write_file("/file".as_ref(), b"memory").unwrap();
+                Arc::new(fs)
+            } else {
+                Arc::new(RelFs::new(tmp.path()))
+            };
+
+            let expected = if in_memory { "memory" } else { "disk" };
+            assert_eq!(fs.read_to_string("file".as_ref()).unwrap(), expected);
+
+            let entries: Vec<_> = fs
+                .dyn_read_dir("/".as_ref())
+                .unwrap()
+                .map(|e| e.unwrap().file_type())
+                .collect();
+            assert_eq!(entries, [FileType::File]);
+        }
+    }
+
+    #[test]
+    fn suggestions() {
+        let mem = MemFs::with_files(&[("/etc/app.toml", ""), ("/etc/conf.d/a.toml", "")]);
+        let fs: Arc<dyn DynLogixVfs> = Arc::new(mem.clone());
+
+        for path in ["/etc/app.tml", "/etc/conf/a.toml"] {
+            assert_eq!(
+                fs.suggest_paths(path.as_ref()),
+                mem.suggest_paths(path.as_ref()),
+                "{path:?}"
+            );
+        }
+        assert_eq!(
+            fs.suggest_paths("/etc/app.tml".as_ref()),
+            [PathBuf::from("/etc/app.toml")]
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// A readable and seekable file, used for trait objects such as
+/// [crate::DynFile]
+pub trait ReadSeek: Read + Seek {}
+
+impl<T: Read + Seek + ?Sized> ReadSeek for T {}
+
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Makes a reader that can't seek, such as a stream, usable as
+/// [crate::LogixVfs::RoFile]. Everything read is kept in memory so seeking
+/// backwards is possible, while seeking relative to the end reads the rest
+/// of the stream.
+#[derive(Debug)]
+pub struct SeekAdapter<R> {
+    inner: R,
+    buf: Vec<u8>,
+    pos: u64,
+    eof: bool,
+}
+
+impl<R: Read> SeekAdapter<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buf: Vec::new(),
+            pos: 0,
+            eof: false,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Read from the stream until at least `end` bytes are buffered or the
+    /// stream ends
+    fn fill_to(&mut self, end: u64) -> io::Result<()> {
+        while !self.eof && (self.buf.len() as u64) < end {
+            let len = self.buf.len();
+            self.buf.resize(len + CHUNK_SIZE, 0);
+            let res = self.inner.read(&mut self.buf[len..]);
+            self.buf.truncate(len + res.as_ref().copied().unwrap_or(0));
+            match res {
+                Ok(0) => self.eof = true,
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for SeekAdapter<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        self.fill_to(self.pos.saturating_add(out.len() as u64))?;
+        let start = usize::try_from(self.pos)
+            .unwrap_or(usize::MAX)
+            .min(self.buf.len());
+        let len = out.len().min(self.buf.len() - start);
+        out[..len].copy_from_slice(&self.buf[start..start + len]);
+        self.pos += len as u64;
+        Ok(len)
+    }
+}
+
+impl<R: Read> Seek for SeekAdapter<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::Current(delta) => self.pos.checked_add_signed(delta),
+            SeekFrom::End(delta) => {
+                self.fill_to(u64::MAX)?;
+                (self.buf.len() as u64).checked_add_signed(delta)
+            }
+        };
+        self.pos = target.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+
+    use super::*;
+    use crate::{LogixVfs, LogixVfsMut, MemFs};
+
+    /// A reader that only hands out a few bytes at a time and can't seek
+    struct Stream<'a>(&'a [u8]);
+
+    impl Read for Stream<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(self.0.len()).min(3);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn seek_adapter() {
+        let mut file = SeekAdapter::new(Stream(b"header:0123456789"));
+
+        let mut header = [0; 7];
+        file.read_exact(&mut header).unwrap();
+        assert_eq!(&header, b"header:");
+
+        assert_eq!(file.seek(SeekFrom::Current(5)).unwrap(), 12);
+        let mut rest = String::new();
+        file.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "56789");
+
+        assert_eq!(file.seek(SeekFrom::End(-3)).unwrap(), 14);
+        let mut tail = String::new();
+        file.read_to_string(&mut tail).unwrap();
+        assert_eq!(tail, "789");
+
+        assert_eq!(file.seek(SeekFrom::Start(100)).unwrap(), 100);
+        assert_eq!(file.read(&mut [0; 4]).unwrap(), 0);
+        assert_eq!(
+            file.seek(SeekFrom::Current(-101)).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn open_buffered() {
+        let mut fs = MemFs::default();
+        fs.write_file("/file".as_ref(), b"first\nsecond\n").unwrap();
+
+        let mut file = fs.open_buffered("/file".as_ref()).unwrap();
+        let mut line = String::new();
+        file.read_line(&mut line).unwrap();
+        assert_eq!(line, "first\n");
+
+        file.rewind().unwrap();
+        let lines: Vec<_> = file.lines().map(|l| l.unwrap()).collect();
+        assert_eq!(lines, ["first", "second"]);
+    }
+}
@@ -0,0 +1,577 @@
+use std::{
+    collections::BTreeSet,
+    iter::Peekable,
+    path::{Component, Path, PathBuf},
+    str::Chars,
+};
+
+use crate::{Error, LogixVfs, LogixVfsDirEntry};
+
+/// A single token in a pattern for a file name
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Token {
+    Char(char),
+    /// `*`, any number of characters
+    Any,
+    /// `?`, exactly one character
+    One,
+    /// `[...]`, one of the listed characters or ranges, or one that isn't
+    /// listed when negated using `[!...]` or `[^...]`
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+impl Token {
+    fn matches_char(&self, c: char) -> bool {
+        match self {
+            Self::Char(t) => *t == c,
+            Self::Any | Self::One => true,
+            Self::Class { negated, ranges } => {
+                ranges.iter().any(|(lo, hi)| (*lo..=*hi).contains(&c)) != *negated
+            }
+        }
+    }
+}
+
+/// One component of the pattern, that is the text between two slashes
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Segment {
+    /// A name without any special characters
+    Literal(String),
+    /// `**`, any number of directories
+    AnyDirs,
+    /// A name with wildcards, brace alternation is expanded so this holds
+    /// one token list for each alternative
+    Pattern(Vec<Vec<Token>>),
+}
+
+impl Segment {
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            Self::Literal(lit) => lit == name,
+            Self::AnyDirs => !name.starts_with('.'),
+            Self::Pattern(alts) => {
+                let name = name.chars().collect::<Vec<_>>();
+                alts.iter().any(|tokens| {
+                    // NOTE(2026.10): Like in a shell, hidden files must be matched explicitly
+                    if name.first() == Some(&'.') && tokens.first() != Some(&Token::Char('.')) {
+                        return false;
+                    }
+                    match_tokens(tokens, &name)
+                })
+            }
+        }
+    }
+}
+
+/// Match `name` against `tokens` greedily, on a mismatch only the most
+/// recent `*` is extended. Going back to an earlier `*` is never needed, as
+/// anything it could take can also be taken by the later one.
+fn match_tokens(tokens: &[Token], name: &[char]) -> bool {
+    let (mut t, mut n) = (0, 0);
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match tokens.get(t) {
+            Some(Token::Any) => {
+                backtrack = Some((t, n));
+                t += 1;
+            }
+            Some(token) if token.matches_char(name[n]) => {
+                t += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((any, start)) => {
+                    backtrack = Some((any, start + 1));
+                    t = any + 1;
+                    n = start + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    tokens[t..].iter().all(|t| *t == Token::Any)
+}
+
+/// Match `names` against `segments`. As `**` can't match hidden names the
+/// trick used by [match_tokens] doesn't work, instead the positions that
+/// failed to match are remembered so each is only tried once.
+fn match_segments(segments: &[Segment], names: &[&str]) -> bool {
+    fn inner(
+        segments: &[Segment],
+        names: &[&str],
+        pos: (usize, usize),
+        failed: &mut BTreeSet<(usize, usize)>,
+    ) -> bool {
+        if failed.contains(&pos) {
+            return false;
+        }
+        let (s, n) = pos;
+        let ret = match segments.get(s) {
+            None => n == names.len(),
+            Some(seg @ Segment::AnyDirs) if s + 1 == segments.len() => {
+                n < names.len() && names[n..].iter().all(|name| seg.matches(name))
+            }
+            Some(seg @ Segment::AnyDirs) => {
+                inner(segments, names, (s + 1, n), failed)
+                    || (n < names.len()
+                        && seg.matches(names[n])
+                        && inner(segments, names, (s, n + 1), failed))
+            }
+            Some(seg) => {
+                n < names.len()
+                    && seg.matches(names[n])
+                    && inner(segments, names, (s + 1, n + 1), failed)
+            }
+        };
+        if !ret {
+            failed.insert(pos);
+        }
+        ret
+    }
+
+    inner(segments, names, (0, 0), &mut BTreeSet::new())
+}
+
+/// A compiled glob pattern. The supported syntax is:
+///
+/// * `*` matches any number of characters within a file name
+/// * `?` matches exactly one character
+/// * `[abc]`, `[a-z]` and the negated `[!a-z]` match one character
+/// * `{a,b}` matches either of the alternatives, which may be nested but
+///   can't contain `/`
+/// * `**` as a whole path component matches any number of directories
+/// * `\` makes the next character lose its special meaning
+///
+/// Wildcards never match a leading `.`, so hidden files are only found if
+/// the pattern explicitly starts with a dot. A trailing `/` only matches
+/// directories.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Pattern {
+    pattern: String,
+    absolute: bool,
+    dirs_only: bool,
+    segments: Vec<Segment>,
+}
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::InvalidPattern {
+            pattern: pattern.to_owned(),
+            reason: reason.to_owned(),
+        };
+
+        let mut segments = Vec::new();
+        for part in pattern.split('/').filter(|s| !s.is_empty()) {
+            let segment = if part == "**" {
+                if segments.last() == Some(&Segment::AnyDirs) {
+                    continue;
+                }
+                Segment::AnyDirs
+            } else {
+                let mut it = part.chars().peekable();
+                let alts = parse_seq(&mut it, 0).map_err(invalid)?;
+                if it.next().is_some() {
+                    return Err(invalid("unexpected end of alternatives"));
+                }
+                match alts.as_slice() {
+                    [tokens] if tokens.iter().all(|t| matches!(t, Token::Char(_))) => {
+                        Segment::Literal(
+                            tokens
+                                .iter()
+                                .map(|t| match t {
+                                    Token::Char(c) => *c,
+                                    _ => unreachable!(),
+                                })
+                                .collect(),
+                        )
+                    }
+                    _ => Segment::Pattern(alts),
+                }
+            };
+            segments.push(segment);
+        }
+
+        if segments.is_empty() {
+            return Err(invalid("the pattern is empty"));
+        }
+
+        Ok(Self {
+            pattern: pattern.to_owned(),
+            absolute: pattern.starts_with('/'),
+            dirs_only: pattern.ends_with('/'),
+            segments,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns true if `path` matches the pattern, without checking if it
+    /// exists. A trailing `/` in the pattern is ignored as the type of the
+    /// path is unknown.
+    pub fn matches(&self, path: &Path) -> bool {
+        if self.absolute != path.has_root() {
+            return false;
+        }
+
+        let mut names = Vec::new();
+        for component in path.components() {
+            match component {
+                Component::Normal(name) => match name.to_str() {
+                    Some(name) => names.push(name),
+                    None => return false,
+                },
+                Component::ParentDir => names.push(".."),
+                Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+            }
+        }
+        match_segments(&self.segments, &names)
+    }
+
+    /// Find all paths in `vfs` matching the pattern, sorted by path. Only
+    /// directories that can still lead to a match are read.
+    pub fn find(&self, vfs: &impl LogixVfs) -> Result<Vec<PathBuf>, Error> {
+        let base = if self.absolute { "/" } else { "" };
+        let mut found = BTreeSet::new();
+        let mut visited = BTreeSet::new();
+        self.visit(
+            vfs,
+            Path::new(base),
+            &self.segments,
+            &mut found,
+            &mut visited,
+        )?;
+        Ok(found.into_iter().collect())
+    }
+
+    fn visit(
+        &self,
+        vfs: &impl LogixVfs,
+        dir: &Path,
+        segments: &[Segment],
+        found: &mut BTreeSet<PathBuf>,
+        visited: &mut BTreeSet<(PathBuf, usize)>,
+    ) -> Result<(), Error> {
+        let Some((segment, rest)) = segments.split_first() else {
+            return Ok(());
+        };
+        // NOTE(2026.10): Several `**` can reach the same directory in many ways, only visit it once
+        if !visited.insert((dir.to_path_buf(), segments.len())) {
+            return Ok(());
+        }
+
+        match segment {
+            Segment::Literal(name) => {
+                let path = dir.join(name);
+                if !rest.is_empty() {
+                    return self.visit(vfs, &path, rest, found, visited);
+                }
+                match vfs.metadata(&path) {
+                    Ok(meta) if !self.dirs_only || meta.is_dir() => {
+                        found.insert(path);
+                    }
+                    Ok(_) | Err(Error::NotFound { .. } | Error::NotADirectory { .. }) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            Segment::AnyDirs => {
+                if !rest.is_empty() {
+                    self.visit(vfs, dir, rest, found, visited)?;
+                }
+                for entry in list(vfs, dir)? {
+                    let name = entry.path().file_name().and_then(|n| n.to_str());
+                    if !name.is_some_and(|name| segment.matches(name)) {
+                        continue;
+                    }
+                    let path = dir.join(entry.path().file_name().unwrap_or_default());
+                    if rest.is_empty() && (!self.dirs_only || entry.is_dir()) {
+                        found.insert(path.clone());
+                    }
+                    // NOTE(2026.10): Links are not followed here to avoid walking in circles
+                    if entry.is_dir() {
+                        self.visit(vfs, &path, segments, found, visited)?;
+                    }
+                }
+            }
+            Segment::Pattern(_) => {
+                for entry in list(vfs, dir)? {
+                    let Some(name) = entry.path().file_name() else {
+                        continue;
+                    };
+                    if !name.to_str().is_some_and(|name| segment.matches(name)) {
+                        continue;
+                    }
+                    let path = dir.join(name);
+                    if !rest.is_empty() {
+                        if entry.is_dir() || entry.is_symlink() {
+                            self.visit(vfs, &path, rest, found, visited)?;
+                        }
+                    } else if !self.dirs_only
+                        || entry.is_dir()
+                        || (entry.is_symlink() && vfs.metadata(&path).is_ok_and(|m| m.is_dir()))
+                    {
+                        found.insert(path);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// List a directory, treating a missing directory as an empty one as it
+/// just means nothing matches
+fn list<V: LogixVfs>(vfs: &V, dir: &Path) -> Result<Vec<V::DirEntry>, Error> {
+    match vfs.read_dir(dir) {
+        Ok(it) => it.collect(),
+        Err(Error::NotFound { .. } | Error::NotADirectory { .. }) => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Parse a sequence of tokens up to the end of the input, or up to a `,`
+/// or `}` when inside braces, returning one token list for each expanded
+/// alternative
+fn parse_seq(it: &mut Peekable<Chars>, depth: usize) -> Result<Vec<Vec<Token>>, &'static str> {
+    let mut alts = vec![Vec::new()];
+
+    while let Some(&c) = it.peek() {
+        let token = match c {
+            ',' | '}' if depth > 0 => break,
+            '{' => {
+                it.next();
+                let mut options = Vec::new();
+                loop {
+                    options.extend(parse_seq(it, depth + 1)?);
+                    match it.next() {
+                        Some(',') => continue,
+                        Some('}') => break,
+                        _ => return Err("missing `}`"),
+                    }
+                }
+                alts = alts
+                    .iter()
+                    .flat_map(|prefix| {
+                        options.iter().map(move |option| {
+                            let mut tokens = prefix.clone();
+                            for t in option {
+                                push_token(&mut tokens, t.clone());
+                            }
+                            tokens
+                        })
+                    })
+                    .collect();
+                continue;
+            }
+            '*' => Token::Any,
+            '?' => Token::One,
+            '[' => {
+                it.next();
+                parse_class(it)?
+            }
+            '\\' => {
+                it.next();
+                Token::Char(it.peek().copied().ok_or("trailing `\\`")?)
+            }
+            c => Token::Char(c),
+        };
+        if !matches!(token, Token::Class { .. }) {
+            it.next();
+        }
+        for tokens in &mut alts {
+            push_token(tokens, token.clone());
+        }
+    }
+
+    Ok(alts)
+}
+
+fn push_token(tokens: &mut Vec<Token>, token: Token) {
+    // NOTE(2026.10): Repeated `*` would only make matching slower
+    if token == Token::Any && tokens.last() == Some(&Token::Any) {
+        return;
+    }
+    tokens.push(token);
+}
+
+/// Parse a character class, the opening `[` is already consumed
+fn parse_class(it: &mut Peekable<Chars>) -> Result<Token, &'static str> {
+    let negated = it.next_if(|c| *c == '!' || *c == '^').is_some();
+    let mut ranges = Vec::new();
+    let mut first = true;
+
+    loop {
+        let c = match it.next() {
+            Some(']') if !first => break,
+            Some(c) => c,
+            None => return Err("missing `]`"),
+        };
+        first = false;
+
+        let lo = if c == '\\' {
+            it.next().ok_or("missing `]`")?
+        } else {
+            c
+        };
+        let hi = if it.next_if_eq(&'-').is_some() {
+            match it.next_if(|c| *c != ']') {
+                Some(hi) => hi,
+                None => {
+                    // NOTE(2026.10): A `-` right before the `]` is just a character
+                    ranges.push(('-', '-'));
+                    lo
+                }
+            }
+        } else {
+            lo
+        };
+        if hi < lo {
+            return Err("invalid range in `[...]`");
+        }
+        ranges.push((lo, hi));
+    }
+
+    Ok(Token::Class { negated, ranges })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemFs;
+
+    fn test_fs() -> MemFs {
+        MemFs::with_files(&[
+            ("/conf.d/10-base.toml", ""),
+            ("/conf.d/20-extra.toml", ""),
+            ("/conf.d/README.md", ""),
+            ("/conf.d/nested/30-deep.toml", ""),
+            ("/conf.d/.hidden.toml", ""),
+            ("/main.logix", ""),
+            ("/lib/a.logix", ""),
+            ("/lib/b/c.logix", ""),
+            ("/lib/b/c.txt", ""),
+        ])
+    }
+
+    fn glob(fs: &MemFs, pattern: &str) -> Vec<String> {
+        fs.glob(pattern)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.to_str().unwrap().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn find() {
+        let fs = test_fs();
+
+        assert_eq!(
+            glob(&fs, "conf.d/*.toml"),
+            ["conf.d/10-base.toml", "conf.d/20-extra.toml"]
+        );
+        assert_eq!(
+            glob(&fs, "/conf.d/**/*.toml"),
+            [
+                "/conf.d/10-base.toml",
+                "/conf.d/20-extra.toml",
+                "/conf.d/nested/30-deep.toml"
+            ]
+        );
+        assert_eq!(
+            glob(&fs, "**/*.logix"),
+            ["lib/a.logix", "lib/b/c.logix", "main.logix"]
+        );
+        assert_eq!(glob(&fs, "conf.d/.*"), ["conf.d/.hidden.toml"]);
+        assert_eq!(
+            glob(&fs, "conf.d/[0-9]?-{base,ex*}.toml"),
+            ["conf.d/10-base.toml", "conf.d/20-extra.toml"]
+        );
+        assert_eq!(
+            glob(&fs, "lib/b/c.{logix,txt}"),
+            ["lib/b/c.logix", "lib/b/c.txt"]
+        );
+        assert_eq!(glob(&fs, "lib/[!a]*"), ["lib/b"]);
+        assert_eq!(glob(&fs, "*/"), ["conf.d", "lib"]);
+        assert_eq!(
+            glob(&fs, "lib/**"),
+            ["lib/a.logix", "lib/b", "lib/b/c.logix", "lib/b/c.txt"]
+        );
+        assert_eq!(glob(&fs, "main.logix"), ["main.logix"]);
+        assert_eq!(glob(&fs, "missing/*.toml"), Vec::<String>::new());
+        assert_eq!(glob(&fs, "main.logix/*"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn matches() {
+        let pattern = Pattern::new("conf.d/**/{a,b[0-9]}.toml").unwrap();
+        assert!(pattern.matches("conf.d/a.toml".as_ref()));
+        assert!(pattern.matches("conf.d/x/y/b7.toml".as_ref()));
+        assert!(!pattern.matches("conf.d/.x/a.toml".as_ref()));
+        assert!(!pattern.matches("conf.d/c.toml".as_ref()));
+        assert!(!pattern.matches("/conf.d/a.toml".as_ref()));
+
+        assert!(Pattern::new("/**").unwrap().matches("/a/b".as_ref()));
+        assert!(!Pattern::new("/**").unwrap().matches("/".as_ref()));
+        assert!(Pattern::new("\\*[]-]").unwrap().matches("*]".as_ref()));
+        assert!(Pattern::new("a{,.bak}").unwrap().matches("a".as_ref()));
+
+        for pattern in ["", "/", "a{b", "a[b", "[z-a]", "a\\"] {
+            assert!(
+                matches!(
+                    Pattern::new(pattern),
+                    Err(Error::InvalidPattern { pattern: p, .. }) if p == pattern
+                ),
+                "{pattern:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn many_wildcards() {
+        let name = "a".repeat(200);
+        assert!(!Pattern::new("*a*a*a*a*a*a*a*a*b")
+            .unwrap()
+            .matches(name.as_ref()));
+        assert!(Pattern::new("*a*a*a*a*a*a*a*a*")
+            .unwrap()
+            .matches(name.as_ref()));
+
+        let path = vec!["a"; 200].join("/");
+        assert!(!Pattern::new("**/a/**/a/**/a/**/a/**/a/**/b")
+            .unwrap()
+            .matches(path.as_ref()));
+
+        let fs = MemFs::with_files(&[(&format!("/{}/file", vec!["a"; 30].join("/")), "")]);
+        assert_eq!(glob(&fs, "**/a/**/a/**/a/**/a/**/b"), Vec::<String>::new());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rel_fs() {
+        use crate::RelFs;
+
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("conf.d/nested")).unwrap();
+        std::fs::write(tmp.path().join("conf.d/b.toml"), "").unwrap();
+        std::fs::write(tmp.path().join("conf.d/a.toml"), "").unwrap();
+        std::fs::write(tmp.path().join("conf.d/nested/c.toml"), "").unwrap();
+        std::os::unix::fs::symlink("..", tmp.path().join("conf.d/nested/up")).unwrap();
+
+        let fs = RelFs::new(tmp.path());
+        assert_eq!(
+            fs.glob("conf.d/**/*.toml").unwrap(),
+            [
+                PathBuf::from("conf.d/a.toml"),
+                "conf.d/b.toml".into(),
+                "conf.d/nested/c.toml".into()
+            ]
+        );
+    }
+}
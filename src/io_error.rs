@@ -0,0 +1,160 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// The operation that failed, recorded by [IoError]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Operation {
+    Canonicalize,
+    OpenFile,
+    ReadFile,
+    ReadDir,
+    Metadata,
+    ReadLink,
+    WriteFile,
+    AppendFile,
+    CreateDir,
+    RemoveFile,
+    RemoveDir,
+    Rename,
+    /// The operation is not known
+    Other,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Canonicalize => "canonicalize",
+            Self::OpenFile => "open",
+            Self::ReadFile => "read",
+            Self::ReadDir => "read directory",
+            Self::Metadata => "query metadata of",
+            Self::ReadLink => "read link",
+            Self::WriteFile => "write",
+            Self::AppendFile => "append to",
+            Self::CreateDir => "create directory",
+            Self::RemoveFile => "remove",
+            Self::RemoveDir => "remove directory",
+            Self::Rename => "rename",
+            Self::Other => "access",
+        })
+    }
+}
+
+/// An error reported by the operating system, together with the operation
+/// that failed and the paths involved. The original [io::Error] is available
+/// through [IoError::io_error] and [std::error::Error::source].
+#[derive(Clone, Debug)]
+pub struct IoError {
+    op: Operation,
+    path: PathBuf,
+    resolved: Option<PathBuf>,
+    source: Arc<io::Error>,
+}
+
+impl IoError {
+    pub fn new(op: Operation, path: impl Into<PathBuf>, source: io::Error) -> Self {
+        Self {
+            op,
+            path: path.into(),
+            resolved: None,
+            source: Arc::new(source),
+        }
+    }
+
+    /// Record the path the backend actually used, such as the full path on
+    /// disk for [crate::RelFs]
+    pub fn with_resolved(mut self, resolved: impl Into<PathBuf>) -> Self {
+        self.resolved = Some(resolved.into());
+        self
+    }
+
+    pub fn operation(&self) -> Operation {
+        self.op
+    }
+
+    /// The path as given by the caller
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The path used by the backend, if it differs from [IoError::path]
+    pub fn resolved_path(&self) -> Option<&Path> {
+        self.resolved.as_deref()
+    }
+
+    pub fn io_error(&self) -> &io::Error {
+        &self.source
+    }
+
+    pub fn kind(&self) -> io::ErrorKind {
+        self.source.kind()
+    }
+
+    pub fn raw_os_error(&self) -> Option<i32> {
+        self.source.raw_os_error()
+    }
+
+    pub(crate) fn map_path(mut self, f: impl FnOnce(PathBuf) -> PathBuf) -> Self {
+        self.path = f(self.path);
+        self
+    }
+
+    /// The fields used for comparisons, the io::Error itself can't be compared
+    fn key(&self) -> (Operation, &Path, Option<&Path>, io::ErrorKind, Option<i32>) {
+        (
+            self.op,
+            &self.path,
+            self.resolved.as_deref(),
+            self.kind(),
+            self.raw_os_error(),
+        )
+    }
+}
+
+impl fmt::Display for IoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Failed to {} {:?}", self.op, self.path)?;
+        if let Some(resolved) = &self.resolved {
+            write!(f, " (resolved to {resolved:?})")?;
+        }
+        write!(f, ": {}", self.source)
+    }
+}
+
+impl std::error::Error for IoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.source)
+    }
+}
+
+impl PartialEq for IoError {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for IoError {}
+
+impl PartialOrd for IoError {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for IoError {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl Hash for IoError {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
@@ -5,25 +5,132 @@ use std::{
     path::{Path, PathBuf},
 };
 
+pub mod cow_fs;
+#[cfg(unix)]
+mod dir_fd;
+mod dyn_vfs;
+mod file;
+pub mod glob;
+mod io_error;
+pub mod materialize;
 pub mod mem_fs;
+mod metadata;
+pub mod mount_fs;
+pub mod overlay_fs;
 pub mod rel_fs;
+pub mod sub_fs;
+mod suggest;
 mod utils;
+pub mod walk;
 
-pub use crate::{mem_fs::MemFs, rel_fs::RelFs};
+pub use crate::{
+    cow_fs::CowFs,
+    dyn_vfs::{DynDirEntry, DynFile, DynLogixVfs, DynReadDir},
+    file::{ReadSeek, SeekAdapter},
+    io_error::{IoError, Operation},
+    materialize::materialize,
+    mem_fs::MemFs,
+    metadata::{FileType, Metadata},
+    mount_fs::MountFs,
+    overlay_fs::OverlayFs,
+    rel_fs::RelFs,
+    sub_fs::SubFs,
+    walk::walk,
+};
 
-#[derive(thiserror::Error, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// The errors reported by the file systems. A `source` field holds the
+/// [IoError] an error was converted from when the operating system reported
+/// it, and is [None] when the problem was detected by the crate itself.
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Error {
-    #[error("Failed to locate {path:?}")]
-    NotFound { path: PathBuf },
+    /// The path does not exist. The suggestions are similar paths that do
+    /// exist, they are only filled in by [Error::with_suggestions].
+    #[error("Failed to locate {path:?}{}", DidYouMean(.suggestions))]
+    NotFound {
+        path: PathBuf,
+        suggestions: Vec<PathBuf>,
+        source: Option<IoError>,
+    },
 
     #[error("Failed to access {path:?}")]
-    AccessDenied { path: PathBuf },
+    AccessDenied {
+        path: PathBuf,
+        source: Option<IoError>,
+    },
 
     #[error("The path {path:?} is outside acceptable bounds")]
     PathOutsideBounds { path: PathBuf },
 
     #[error("The path {path:?} is not a directory")]
-    NotADirectory { path: PathBuf },
+    NotADirectory {
+        path: PathBuf,
+        source: Option<IoError>,
+    },
+
+    /// A directory was found where the operation needs something else, for
+    /// example when writing or removing a file
+    #[error("The path {path:?} is a directory")]
+    IsADirectory {
+        path: PathBuf,
+        source: Option<IoError>,
+    },
+
+    #[error("The path {path:?} already exists")]
+    AlreadyExists {
+        path: PathBuf,
+        source: Option<IoError>,
+    },
+
+    /// The directory has to be empty for the operation, for example when
+    /// removing it or replacing it by renaming another directory
+    #[error("The directory {path:?} is not empty")]
+    DirectoryNotEmpty {
+        path: PathBuf,
+        source: Option<IoError>,
+    },
+
+    /// The path exists but is not a regular file that can be opened
+    #[error("The path {path:?} is not a file")]
+    NotAFile { path: PathBuf },
+
+    /// The path can't be used for the operation, for example because it has
+    /// an unsupported prefix or lacks a parent directory
+    #[error("The path {path:?} is invalid")]
+    InvalidPath {
+        path: PathBuf,
+        source: Option<IoError>,
+    },
+
+    #[error("The path {path:?} is read-only")]
+    ReadOnly {
+        path: PathBuf,
+        source: Option<IoError>,
+    },
+
+    /// Resolving the path needed too many symbolic links, or they form a loop
+    #[error("Too many levels of symbolic links in {path:?}")]
+    TooManySymlinks {
+        path: PathBuf,
+        source: Option<IoError>,
+    },
+
+    /// The file is not valid UTF-8, `offset` is the position of the first
+    /// invalid byte
+    #[error("The file {path:?} is not valid UTF-8, invalid data at byte {offset}")]
+    InvalidUtf8 { path: PathBuf, offset: usize },
+
+    /// Loading the path would go beyond a configured limit on the total size
+    #[error("Loading {path:?} exceeds the size limit of {limit} bytes")]
+    SizeLimitExceeded { path: PathBuf, limit: u64 },
+
+    /// A glob pattern could not be parsed, `reason` describes what is wrong
+    #[error("Invalid glob pattern {pattern:?}, {reason}")]
+    InvalidPattern { pattern: String, reason: String },
+
+    /// An error from the operating system that has no variant of its own,
+    /// such as running out of file descriptors or an I/O failure
+    #[error(transparent)]
+    Io(IoError),
 
     /// Used for other errors that is not defined already. Do not depend on this
     /// for anything other than logging. If you need to check an error that is
@@ -32,33 +139,230 @@ pub enum Error {
     Other(String),
 }
 
+#[cfg(test)]
 impl Error {
+    /// Drop the error from the operating system, so tests can compare the
+    /// error with one created by hand
+    pub(crate) fn without_source(mut self) -> Self {
+        match &mut self {
+            Self::NotFound { source, .. }
+            | Self::AccessDenied { source, .. }
+            | Self::NotADirectory { source, .. }
+            | Self::IsADirectory { source, .. }
+            | Self::AlreadyExists { source, .. }
+            | Self::DirectoryNotEmpty { source, .. }
+            | Self::InvalidPath { source, .. }
+            | Self::ReadOnly { source, .. }
+            | Self::TooManySymlinks { source, .. } => *source = None,
+            _ => {}
+        }
+        self
+    }
+}
+
+struct DidYouMean<'a>(&'a [PathBuf]);
+
+impl std::fmt::Display for DidYouMean<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (i, path) in self.0.iter().enumerate() {
+            match i {
+                0 => write!(f, ", did you mean {path:?}")?,
+                i if i + 1 == self.0.len() => write!(f, " or {path:?}")?,
+                _ => write!(f, ", {path:?}")?,
+            }
+        }
+        if !self.0.is_empty() {
+            f.write_str("?")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error {
+    /// A [Error::NotFound] without any suggestions
+    pub fn not_found(path: impl Into<PathBuf>) -> Self {
+        Self::NotFound {
+            path: path.into(),
+            suggestions: Vec::new(),
+            source: None,
+        }
+    }
+
+    /// Look for paths similar to the one that was not found, for example
+    /// with a typo or a different extension, using [LogixVfs::suggest_paths].
+    /// Other errors are returned unchanged.
+    pub fn with_suggestions(self, vfs: &impl LogixVfs) -> Self {
+        match self {
+            Self::NotFound {
+                path,
+                suggestions,
+                source,
+            } if suggestions.is_empty() => {
+                let suggestions = vfs.suggest_paths(&path);
+                Self::NotFound {
+                    path,
+                    suggestions,
+                    source,
+                }
+            }
+            e => e,
+        }
+    }
+
+    /// Rewrite the path reported by the error, used by file systems wrapping
+    /// another file system that sees different paths
+    pub(crate) fn map_path(self, mut f: impl FnMut(PathBuf) -> PathBuf) -> Self {
+        match self {
+            Self::NotFound {
+                path,
+                suggestions,
+                source,
+            } => Self::NotFound {
+                path: f(path),
+                suggestions: suggestions.into_iter().map(&mut f).collect(),
+                source: source.map(|e| e.map_path(&mut f)),
+            },
+            Self::AccessDenied { path, source } => Self::AccessDenied {
+                path: f(path),
+                source: source.map(|e| e.map_path(&mut f)),
+            },
+            Self::PathOutsideBounds { path } => Self::PathOutsideBounds { path: f(path) },
+            Self::NotADirectory { path, source } => Self::NotADirectory {
+                path: f(path),
+                source: source.map(|e| e.map_path(&mut f)),
+            },
+            Self::IsADirectory { path, source } => Self::IsADirectory {
+                path: f(path),
+                source: source.map(|e| e.map_path(&mut f)),
+            },
+            Self::AlreadyExists { path, source } => Self::AlreadyExists {
+                path: f(path),
+                source: source.map(|e| e.map_path(&mut f)),
+            },
+            Self::DirectoryNotEmpty { path, source } => Self::DirectoryNotEmpty {
+                path: f(path),
+                source: source.map(|e| e.map_path(&mut f)),
+            },
+            Self::NotAFile { path } => Self::NotAFile { path: f(path) },
+            Self::InvalidPath { path, source } => Self::InvalidPath {
+                path: f(path),
+                source: source.map(|e| e.map_path(&mut f)),
+            },
+            Self::ReadOnly { path, source } => Self::ReadOnly {
+                path: f(path),
+                source: source.map(|e| e.map_path(&mut f)),
+            },
+            Self::TooManySymlinks { path, source } => Self::TooManySymlinks {
+                path: f(path),
+                source: source.map(|e| e.map_path(&mut f)),
+            },
+            Self::InvalidUtf8 { path, offset } => Self::InvalidUtf8 {
+                path: f(path),
+                offset,
+            },
+            Self::SizeLimitExceeded { path, limit } => Self::SizeLimitExceeded {
+                path: f(path),
+                limit,
+            },
+            Self::Io(e) => Self::Io(e.map_path(f)),
+            Self::InvalidPattern { .. } | Self::Other(_) => self,
+        }
+    }
+
+    /// The error reported by the operating system, either held by
+    /// [Error::Io] or the `source` of a more specific variant
+    pub fn io_error(&self) -> Option<&IoError> {
+        match self {
+            Self::NotFound { source, .. }
+            | Self::AccessDenied { source, .. }
+            | Self::NotADirectory { source, .. }
+            | Self::IsADirectory { source, .. }
+            | Self::AlreadyExists { source, .. }
+            | Self::DirectoryNotEmpty { source, .. }
+            | Self::InvalidPath { source, .. }
+            | Self::ReadOnly { source, .. }
+            | Self::TooManySymlinks { source, .. } => source.as_ref(),
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+
     pub fn to_io_error(&self) -> std::io::Error {
+        if let Some(code) = self.io_error().and_then(|e| e.raw_os_error()) {
+            return std::io::Error::from_raw_os_error(code);
+        }
         match self {
             Self::NotFound { .. } => ErrorKind::NotFound.into(),
             Self::AccessDenied { .. } => ErrorKind::PermissionDenied.into(),
             Self::PathOutsideBounds { .. } => ErrorKind::InvalidInput.into(),
-            Self::NotADirectory { .. } => {
-                // TODO(2024.02): Once rust-lang/#86442 is stabilized, this can use ErrorKind::NotADirectory
-                std::io::Error::new(ErrorKind::Other, "Not a directory")
+            Self::NotADirectory { .. } => ErrorKind::NotADirectory.into(),
+            Self::IsADirectory { .. } => ErrorKind::IsADirectory.into(),
+            Self::AlreadyExists { .. } => ErrorKind::AlreadyExists.into(),
+            Self::DirectoryNotEmpty { .. } => ErrorKind::DirectoryNotEmpty.into(),
+            Self::NotAFile { .. } => std::io::Error::new(ErrorKind::InvalidInput, self.to_string()),
+            Self::InvalidPath { .. } => ErrorKind::InvalidInput.into(),
+            Self::ReadOnly { .. } => ErrorKind::ReadOnlyFilesystem.into(),
+            Self::TooManySymlinks { .. } => {
+                #[cfg(unix)]
+                return std::io::Error::from_raw_os_error(rustix::io::Errno::LOOP.raw_os_error());
+                #[cfg(not(unix))]
+                return std::io::Error::other(self.to_string());
+            }
+            Self::InvalidUtf8 { .. } => {
+                std::io::Error::new(ErrorKind::InvalidData, self.to_string())
             }
-            Self::Other(message) => std::io::Error::new(ErrorKind::Other, message.as_str()),
+            Self::SizeLimitExceeded { .. } => {
+                std::io::Error::new(ErrorKind::FileTooLarge, self.to_string())
+            }
+            Self::InvalidPattern { .. } => {
+                std::io::Error::new(ErrorKind::InvalidInput, self.to_string())
+            }
+            Self::Io(e) => std::io::Error::new(e.kind(), e.io_error().to_string()),
+            Self::Other(message) => std::io::Error::other(message.as_str()),
         }
     }
+}
 
-    pub fn from_io(path: PathBuf, e: std::io::Error) -> Self {
-        match e.kind() {
-            ErrorKind::NotFound => Self::NotFound { path },
-            ErrorKind::PermissionDenied => Self::AccessDenied { path },
-            _ => {
-                let msg = e.to_string();
-                // TODO(2024.02): Once rust-lang/#86442 is stabilized, this work-around can be removed
-                match msg.as_str().split_once(" (os error") {
-                    Some(("Not a directory", _)) => Self::NotADirectory { path },
-                    _ => Self::Other(msg),
-                }
-            }
-        }
+impl From<IoError> for Error {
+    /// Errors with a matching variant are converted to it, keeping `e` as the
+    /// source, everything else is kept as [Error::Io]
+    fn from(e: IoError) -> Self {
+        type Variant = fn(PathBuf, Option<IoError>) -> Error;
+
+        #[cfg(unix)]
+        let is_loop = e.raw_os_error() == Some(rustix::io::Errno::LOOP.raw_os_error());
+        #[cfg(not(unix))]
+        let is_loop = false;
+
+        let variant: Variant = match e.kind() {
+            _ if is_loop => |path, source| Self::TooManySymlinks { path, source },
+            ErrorKind::NotFound => |path, source| Self::NotFound {
+                path,
+                suggestions: Vec::new(),
+                source,
+            },
+            ErrorKind::PermissionDenied => |path, source| Self::AccessDenied { path, source },
+            ErrorKind::NotADirectory => |path, source| Self::NotADirectory { path, source },
+            ErrorKind::IsADirectory => |path, source| Self::IsADirectory { path, source },
+            ErrorKind::AlreadyExists => |path, source| Self::AlreadyExists { path, source },
+            ErrorKind::DirectoryNotEmpty => |path, source| Self::DirectoryNotEmpty { path, source },
+            ErrorKind::InvalidFilename => |path, source| Self::InvalidPath { path, source },
+            ErrorKind::ReadOnlyFilesystem => |path, source| Self::ReadOnly { path, source },
+            _ => return Self::Io(e),
+        };
+        variant(e.path().to_path_buf(), Some(e))
+    }
+}
+
+impl From<Error> for std::io::Error {
+    /// The error is kept as the inner error, so the path, the operation and
+    /// the original error can be retrieved using [std::io::Error::get_ref]
+    fn from(e: Error) -> Self {
+        let kind = match e.io_error() {
+            Some(io) => io.kind(),
+            None => e.to_io_error().kind(),
+        };
+        std::io::Error::new(kind, e)
     }
 }
 
@@ -70,11 +374,248 @@ pub trait LogixVfsDirEntry {
 }
 
 pub trait LogixVfs: std::fmt::Debug + Send + Sync {
-    type RoFile: std::io::Read;
+    /// Files must be seekable, wrap the file in a [SeekAdapter] if the
+    /// backend can only stream them
+    type RoFile: std::io::Read + std::io::Seek;
     type DirEntry: LogixVfsDirEntry;
     type ReadDir: Iterator<Item = Result<Self::DirEntry, Error>>;
 
     fn canonicalize_path(&self, path: &Path) -> Result<PathBuf, Error>;
     fn open_file(&self, path: &Path) -> Result<Self::RoFile, Error>;
     fn read_dir(&self, path: &Path) -> Result<Self::ReadDir, Error>;
+
+    /// Query the metadata of a file or directory without opening it
+    fn metadata(&self, path: &Path) -> Result<Metadata, Error>;
+
+    /// Read the target of a symbolic link without following it. Returns
+    /// [Error::InvalidPath] if the path is not a symbolic link, which is
+    /// always the case for backends without symbolic links.
+    fn read_link(&self, path: &Path) -> Result<PathBuf, Error> {
+        self.metadata(path)?;
+        Err(Error::InvalidPath {
+            path: path.to_path_buf(),
+            source: None,
+        })
+    }
+
+    /// Read the whole content of a file
+    fn read(&self, path: &Path) -> Result<Vec<u8>, Error> {
+        use std::io::Read;
+
+        let mut file = self.open_file(path)?;
+        // NOTE(2026.10): Only a hint, the file may change before it is read
+        let len = self.metadata(path).map_or(0, |meta| meta.len());
+        let mut ret = Vec::with_capacity(len.try_into().unwrap_or(0));
+        file.read_to_end(&mut ret)
+            .map_err(|e| IoError::new(Operation::ReadFile, path, e))?;
+        Ok(ret)
+    }
+
+    /// Read the whole content of a file that must be valid UTF-8
+    fn read_to_string(&self, path: &Path) -> Result<String, Error> {
+        String::from_utf8(self.read(path)?).map_err(|e| Error::InvalidUtf8 {
+            path: path.to_path_buf(),
+            offset: e.utf8_error().valid_up_to(),
+        })
+    }
+
+    /// Returns true if the path exists, returns an error if it couldn't be
+    /// determined, for example because access was denied
+    fn try_exists(&self, path: &Path) -> Result<bool, Error> {
+        match self.metadata(path) {
+            Ok(_) => Ok(true),
+            Err(Error::NotFound { .. }) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns true if the path exists, any error is treated as the path not
+    /// existing, use [LogixVfs::try_exists] to tell them apart
+    fn exists(&self, path: &Path) -> bool {
+        self.try_exists(path).unwrap_or(false)
+    }
+
+    /// Paths that exist and look like `path` with a typo, used by
+    /// [Error::with_suggestions]. The default implementation lists the
+    /// parent directory of the first missing component using
+    /// [LogixVfs::read_dir].
+    fn suggest_paths(&self, path: &Path) -> Vec<PathBuf> {
+        suggest::suggest_paths(path, |dir| {
+            let entries = self.read_dir(dir).ok()?;
+            Some(entries.filter_map(|e| Some(e.ok()?.path().file_name()?.to_owned())))
+        })
+    }
+
+    /// Open a file wrapped in a [std::io::BufReader], for reading it line by
+    /// line or parsing it incrementally
+    fn open_buffered(&self, path: &Path) -> Result<std::io::BufReader<Self::RoFile>, Error> {
+        self.open_file(path).map(std::io::BufReader::new)
+    }
+
+    /// Find the paths matching a glob pattern such as `conf.d/**/*.toml`,
+    /// see [glob::Pattern] for the syntax
+    fn glob(&self, pattern: &str) -> Result<Vec<PathBuf>, Error>
+    where
+        Self: Sized,
+    {
+        glob::Pattern::new(pattern)?.find(self)
+    }
+}
+
+/// A [LogixVfs] that can also be modified. Paths are resolved the same way
+/// as for reading, so the same bounds checks apply.
+pub trait LogixVfsMut: LogixVfs {
+    /// Create a file, or replace the content of an existing file
+    fn write_file(&mut self, path: &Path, data: &[u8]) -> Result<(), Error>;
+
+    /// Append to a file, the file is created if it does not exist
+    fn append_file(&mut self, path: &Path, data: &[u8]) -> Result<(), Error>;
+
+    /// Create a directory, the parent directory must already exist
+    fn create_dir(&mut self, path: &Path) -> Result<(), Error>;
+
+    /// Create a directory and all missing parent directories
+    fn create_dir_all(&mut self, path: &Path) -> Result<(), Error>;
+
+    fn remove_file(&mut self, path: &Path) -> Result<(), Error>;
+
+    /// Remove an empty directory
+    fn remove_dir(&mut self, path: &Path) -> Result<(), Error>;
+
+    /// Remove a directory and everything inside it
+    fn remove_dir_all(&mut self, path: &Path) -> Result<(), Error>;
+
+    /// Move a file or directory, replacing `to` if it is a file or an empty
+    /// directory
+    fn rename(&mut self, from: &Path, to: &Path) -> Result<(), Error>;
+
+    /// Copy the content of a file, replacing `to` if it is a file. Returns
+    /// the number of bytes copied.
+    fn copy(&mut self, from: &Path, to: &Path) -> Result<u64, Error> {
+        let data = self.read(from)?;
+        self.write_file(to, &data)?;
+        Ok(data.len() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn io_error_round_trip() {
+        for e in [
+            Error::not_found("a"),
+            Error::AccessDenied {
+                path: "a".into(),
+                source: None,
+            },
+            Error::NotADirectory {
+                path: "a".into(),
+                source: None,
+            },
+            Error::IsADirectory {
+                path: "a".into(),
+                source: None,
+            },
+            Error::AlreadyExists {
+                path: "a".into(),
+                source: None,
+            },
+            Error::DirectoryNotEmpty {
+                path: "a".into(),
+                source: None,
+            },
+            Error::ReadOnly {
+                path: "a".into(),
+                source: None,
+            },
+            #[cfg(unix)]
+            Error::TooManySymlinks {
+                path: "a".into(),
+                source: None,
+            },
+        ] {
+            let back = Error::from(IoError::new(Operation::OpenFile, "a", e.to_io_error()));
+            assert_eq!(
+                std::mem::discriminant(&back),
+                std::mem::discriminant(&e),
+                "{e:?}"
+            );
+            assert_eq!(back.io_error().map(|io| io.path()), Some(Path::new("a")));
+        }
+
+        assert_eq!(
+            Error::InvalidPath {
+                path: "a".into(),
+                source: None,
+            }
+            .to_io_error()
+            .kind(),
+            ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            Error::NotAFile { path: "a".into() }.to_io_error().kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn io_error_context() {
+        use std::error::Error as _;
+
+        let inner = std::io::Error::new(ErrorKind::StorageFull, "disk full");
+        let e = Error::from(
+            IoError::new(Operation::WriteFile, "/a/b", inner).with_resolved("/srv/root/a/b"),
+        );
+        let Error::Io(io) = &e else {
+            panic!("Expected Error::Io, got {e:?}");
+        };
+        assert_eq!(io.operation(), Operation::WriteFile);
+        assert_eq!(io.path(), Path::new("/a/b"));
+        assert_eq!(io.resolved_path(), Some(Path::new("/srv/root/a/b")));
+        assert_eq!(
+            e.to_string(),
+            r#"Failed to write "/a/b" (resolved to "/srv/root/a/b"): disk full"#
+        );
+        assert_eq!(e.source().unwrap().to_string(), "disk full");
+        assert_eq!(
+            e.clone().map_path(|p| p.join("c")).to_string(),
+            r#"Failed to write "/a/b/c" (resolved to "/srv/root/a/b"): disk full"#
+        );
+
+        let back = std::io::Error::from(e.clone());
+        assert_eq!(back.kind(), ErrorKind::StorageFull);
+        assert_eq!(back.get_ref().unwrap().downcast_ref::<Error>(), Some(&e));
+
+        let e = Error::from(
+            IoError::new(
+                Operation::OpenFile,
+                "/a",
+                std::io::Error::from_raw_os_error(2),
+            )
+            .with_resolved("/srv/root/a"),
+        );
+        let Error::NotFound { path, source, .. } = &e else {
+            panic!("Expected Error::NotFound, got {e:?}");
+        };
+        assert_eq!(path, Path::new("/a"));
+        assert_eq!(
+            source.as_ref().map(|io| io.operation()),
+            Some(Operation::OpenFile)
+        );
+        assert_eq!(
+            e.io_error().and_then(|io| io.resolved_path()),
+            Some(Path::new("/srv/root/a"))
+        );
+        assert_eq!(
+            e.source().unwrap().to_string(),
+            source.as_ref().unwrap().to_string()
+        );
+        assert_eq!(e.to_io_error().raw_os_error(), Some(2));
+
+        let back = std::io::Error::from(e.clone());
+        assert_eq!(back.kind(), ErrorKind::NotFound);
+        assert_eq!(back.get_ref().unwrap().downcast_ref::<Error>(), Some(&e));
+    }
 }
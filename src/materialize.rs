@@ -0,0 +1,588 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    rel_fs::write_atomic,
+    utils::temp_name,
+    walk::{walk, Order},
+    Error, FileType, IoError, LogixVfs, LogixVfsDirEntry, Operation, RelFs,
+};
+
+/// Mirror the tree in `src` onto the directory `dest_dir` on disk, see
+/// [Materialize] for the available options
+pub fn materialize<V: LogixVfs>(src: &V, dest_dir: impl AsRef<Path>) -> Materialize<'_, V> {
+    Materialize {
+        src,
+        root: PathBuf::from("/"),
+        dest: dest_dir.as_ref().to_path_buf(),
+        delete_extra: false,
+        atomic_swap: false,
+    }
+}
+
+/// A change needed to make the destination match the source, the paths are
+/// relative to the destination directory
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Change {
+    /// The entry is missing in the destination
+    Create { path: PathBuf, ty: FileType },
+    /// The file has different content, or the entry has a different type
+    Update { path: PathBuf, ty: FileType },
+    /// The entry only exists in the destination
+    Delete { path: PathBuf, ty: FileType },
+}
+
+impl Change {
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Create { path, .. } | Self::Update { path, .. } | Self::Delete { path, .. } => {
+                path
+            }
+        }
+    }
+}
+
+/// Where an entry in the new tree comes from
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Origin {
+    /// The source, the content has to be written
+    Src(FileType),
+    /// The destination, the entry is linked into the new tree unchanged
+    Dest(FileType),
+}
+
+/// A builder for mirroring a tree onto disk, created by [materialize].
+///
+/// Nothing is written if the destination already matches. Otherwise only the
+/// entries that differ are changed inside the destination, each file is
+/// written to a temporary file that is renamed into place, so readers see
+/// either the old or the new content of a file. The destination directory
+/// itself is kept, along with the permissions and owners of the directories
+/// in it, and it may be a symbolic link to the real directory.
+pub struct Materialize<'a, V: LogixVfs> {
+    src: &'a V,
+    root: PathBuf,
+    dest: PathBuf,
+    delete_extra: bool,
+    atomic_swap: bool,
+}
+
+impl<V: LogixVfs> Materialize<'_, V> {
+    /// The directory in the source to mirror, the default is `/`
+    pub fn root(mut self, root: impl AsRef<Path>) -> Self {
+        self.root = root.as_ref().to_path_buf();
+        self
+    }
+
+    /// Delete entries in the destination that don't exist in the source,
+    /// by default they are kept
+    pub fn delete_extra(mut self, yes: bool) -> Self {
+        self.delete_extra = yes;
+        self
+    }
+
+    /// Build the whole new tree in a staging directory next to the
+    /// destination and swap the two, so readers never see a mix of the old
+    /// and the new tree. Unchanged files are hard links to the existing ones.
+    ///
+    /// The destination directory is replaced, so open handles to it keep
+    /// seeing the old tree, a symbolic link at the destination is replaced
+    /// by a directory, and only the permissions of the destination itself are
+    /// carried over. The swap is atomic on Linux and Apple platforms,
+    /// elsewhere the destination is missing for a moment between two renames.
+    pub fn atomic_swap(mut self, yes: bool) -> Self {
+        self.atomic_swap = yes;
+        self
+    }
+
+    /// Compute the changes without writing anything, sorted by path
+    pub fn plan(&self) -> Result<Vec<Change>, Error> {
+        Ok(self.compare()?.0)
+    }
+
+    /// Write the changes to disk and return them, see [Materialize::plan]
+    pub fn apply(&self) -> Result<Vec<Change>, Error> {
+        let (changes, tree) = self.compare()?;
+        if changes.is_empty() {
+            return Ok(changes);
+        }
+
+        if self.atomic_swap {
+            self.apply_swap(&tree)?;
+        } else {
+            self.apply_in_place(&changes)?;
+        }
+        Ok(changes)
+    }
+
+    /// Change only the entries that differ inside the destination
+    fn apply_in_place(&self, changes: &[Change]) -> Result<(), Error> {
+        std::fs::create_dir_all(&self.dest).map_err(io_error(Operation::CreateDir, &self.dest))?;
+
+        // NOTE(2026.10): Reversed so the content of a directory is removed before the directory
+        for change in changes.iter().rev() {
+            let target = self.dest.join(change.path());
+            let existing = match std::fs::symlink_metadata(&target) {
+                Ok(meta) => meta.file_type(),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(io_error(Operation::Metadata, &target)(e)),
+            };
+            let remove = match change {
+                Change::Create { .. } => false,
+                Change::Update { ty, .. } => existing.is_dir() != (*ty == FileType::Dir),
+                Change::Delete { .. } => true,
+            };
+            if !remove {
+                continue;
+            }
+            if existing.is_dir() {
+                std::fs::remove_dir(&target).map_err(io_error(Operation::RemoveDir, &target))?;
+            } else {
+                std::fs::remove_file(&target).map_err(io_error(Operation::RemoveFile, &target))?;
+            }
+        }
+
+        for change in changes {
+            let (Change::Create { path, ty } | Change::Update { path, ty }) = change else {
+                continue;
+            };
+            let target = self.dest.join(path);
+            if *ty == FileType::Dir {
+                match std::fs::create_dir(&target) {
+                    Err(e) if e.kind() != io::ErrorKind::AlreadyExists => {
+                        return Err(io_error(Operation::CreateDir, &target)(e))
+                    }
+                    _ => {}
+                }
+            } else {
+                let data = self.src.read(&self.root.join(path))?;
+                write_atomic(&target, &data).map_err(io_error(Operation::WriteFile, &target))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Build the new tree in a staging directory and swap it with the
+    /// destination
+    fn apply_swap(&self, tree: &BTreeMap<PathBuf, Origin>) -> Result<(), Error> {
+        let (Some(parent), Some(name)) = (self.dest.parent(), self.dest.file_name()) else {
+            return Err(Error::InvalidPath {
+                path: self.dest.clone(),
+                source: None,
+            });
+        };
+        let staging = parent.join(temp_name(name));
+
+        let res = self
+            .build(&staging, tree)
+            .and_then(|()| self.swap(&staging));
+        if res.is_err() {
+            let _ = std::fs::remove_dir_all(&staging);
+        }
+        res
+    }
+
+    /// List the entries of the source and the destination, returning the
+    /// changes and what the new tree is made of
+    fn compare(&self) -> Result<(Vec<Change>, BTreeMap<PathBuf, Origin>), Error> {
+        let src = self.list(self.src, &self.root, true)?;
+        let dest_fs = RelFs::new(&self.dest);
+        let dest = match dest_fs.metadata(Path::new("/")) {
+            Ok(meta) if meta.is_dir() => self.list(&dest_fs, Path::new("/"), false)?,
+            Ok(_) => {
+                return Err(Error::NotADirectory {
+                    path: self.dest.clone(),
+                    source: None,
+                })
+            }
+            Err(Error::NotFound { .. }) => BTreeMap::new(),
+            Err(e) => return Err(e.map_path(|_| self.dest.clone())),
+        };
+
+        let mut changes = Vec::new();
+        let mut tree = BTreeMap::new();
+
+        for (rel, &ty) in &src {
+            let origin = match dest.get(rel) {
+                None => {
+                    changes.push(Change::Create {
+                        path: rel.clone(),
+                        ty,
+                    });
+                    Origin::Src(ty)
+                }
+                Some(FileType::Dir) if ty == FileType::Dir => Origin::Src(ty),
+                Some(FileType::File)
+                    if ty == FileType::File
+                        && self.src.read(&self.root.join(rel))?
+                            == dest_fs.read(&Path::new("/").join(rel))? =>
+                {
+                    Origin::Dest(ty)
+                }
+                Some(_) => {
+                    changes.push(Change::Update {
+                        path: rel.clone(),
+                        ty,
+                    });
+                    Origin::Src(ty)
+                }
+            };
+            tree.insert(rel.clone(), origin);
+        }
+
+        for (rel, &ty) in &dest {
+            if src.contains_key(rel) {
+                continue;
+            }
+            // NOTE(2026.10): Entries below something replaced by a file can't be kept
+            let replaced = rel
+                .ancestors()
+                .skip(1)
+                .any(|p| src.get(p).is_some_and(|ty| *ty != FileType::Dir));
+            if self.delete_extra || replaced {
+                changes.push(Change::Delete {
+                    path: rel.clone(),
+                    ty,
+                });
+            } else {
+                tree.insert(rel.clone(), Origin::Dest(ty));
+            }
+        }
+
+        changes.sort_by(|a, b| a.path().cmp(b.path()));
+        Ok((changes, tree))
+    }
+
+    /// The entries below `root` by relative path, parents sort before their
+    /// content
+    fn list(
+        &self,
+        vfs: &impl LogixVfs,
+        root: &Path,
+        follow_symlinks: bool,
+    ) -> Result<BTreeMap<PathBuf, FileType>, Error> {
+        let mut ret = BTreeMap::new();
+        for entry in walk(vfs, root)
+            .min_depth(1)
+            .order(Order::Sorted)
+            .follow_symlinks(follow_symlinks)
+        {
+            let entry = entry?;
+            let rel = entry
+                .path()
+                .strip_prefix(root)
+                .expect("Walked paths are below the root");
+            ret.insert(rel.to_path_buf(), entry.file_type());
+        }
+        Ok(ret)
+    }
+
+    /// Create the new tree in `staging`
+    fn build(&self, staging: &Path, tree: &BTreeMap<PathBuf, Origin>) -> Result<(), Error> {
+        std::fs::create_dir(staging).map_err(io_error(Operation::CreateDir, staging))?;
+        if let Ok(meta) = std::fs::metadata(&self.dest) {
+            std::fs::set_permissions(staging, meta.permissions())
+                .map_err(io_error(Operation::CreateDir, staging))?;
+        }
+
+        for (rel, origin) in tree {
+            let target = staging.join(rel);
+            match *origin {
+                Origin::Src(FileType::Dir) | Origin::Dest(FileType::Dir) => {
+                    std::fs::create_dir(&target).map_err(io_error(Operation::CreateDir, &target))?
+                }
+                Origin::Src(_) => {
+                    let data = self.src.read(&self.root.join(rel))?;
+                    std::fs::write(&target, data)
+                        .map_err(io_error(Operation::WriteFile, &target))?;
+                }
+                Origin::Dest(ty) => {
+                    let existing = self.dest.join(rel);
+                    link_or_copy(&existing, &target, ty)
+                        .map_err(io_error(Operation::WriteFile, &target))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replace the destination with `staging`
+    fn swap(&self, staging: &Path) -> Result<(), Error> {
+        if !self.dest.exists() {
+            return std::fs::rename(staging, &self.dest)
+                .map_err(io_error(Operation::Rename, &self.dest));
+        }
+
+        #[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+        {
+            use rustix::fs::{renameat_with, RenameFlags, CWD};
+
+            // NOTE(2026.10): Not all file systems support exchanging, fall back to renaming twice
+            if renameat_with(CWD, staging, CWD, &self.dest, RenameFlags::EXCHANGE).is_ok() {
+                return std::fs::remove_dir_all(staging)
+                    .map_err(io_error(Operation::RemoveDir, staging));
+            }
+        }
+
+        let (Some(parent), Some(name)) = (self.dest.parent(), self.dest.file_name()) else {
+            return Err(Error::InvalidPath {
+                path: self.dest.clone(),
+                source: None,
+            });
+        };
+        let old = parent.join(temp_name(name));
+        std::fs::rename(&self.dest, &old).map_err(io_error(Operation::Rename, &self.dest))?;
+        if let Err(e) = std::fs::rename(staging, &self.dest) {
+            let _ = std::fs::rename(&old, &self.dest);
+            return Err(io_error(Operation::Rename, &self.dest)(e));
+        }
+        std::fs::remove_dir_all(&old).map_err(io_error(Operation::RemoveDir, &old))
+    }
+}
+
+fn io_error(op: Operation, path: &Path) -> impl FnOnce(io::Error) -> Error {
+    let path = path.to_path_buf();
+    move |e| IoError::new(op, path, e).into()
+}
+
+/// Hard link an unchanged entry into the new tree, copying regular files if
+/// the file system does not support hard links
+fn link_or_copy(existing: &Path, target: &Path, ty: FileType) -> io::Result<()> {
+    match std::fs::hard_link(existing, target) {
+        Ok(()) => Ok(()),
+        Err(_) if ty == FileType::File => std::fs::copy(existing, target).map(|_| ()),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LogixVfsMut, MemFs};
+
+    fn create(path: &str, ty: FileType) -> Change {
+        Change::Create {
+            path: path.into(),
+            ty,
+        }
+    }
+
+    #[test]
+    fn plan_and_apply() {
+        check_plan_and_apply(false);
+        check_plan_and_apply(true);
+    }
+
+    fn check_plan_and_apply(atomic_swap: bool) {
+        let tmp = tempfile::tempdir().unwrap();
+        let dest = tmp.path().join("out");
+
+        let mut src = MemFs::with_files(&[
+            ("/app.toml", "a = 1\n"),
+            ("/conf.d/db.toml", "url = \"\"\n"),
+        ]);
+        src.create_dir("/empty".as_ref()).unwrap();
+
+        assert_eq!(
+            materialize(&src, &dest)
+                .atomic_swap(atomic_swap)
+                .plan()
+                .unwrap(),
+            [
+                create("app.toml", FileType::File),
+                create("conf.d", FileType::Dir),
+                create("conf.d/db.toml", FileType::File),
+                create("empty", FileType::Dir),
+            ]
+        );
+        assert!(!dest.exists());
+
+        materialize(&src, &dest)
+            .atomic_swap(atomic_swap)
+            .apply()
+            .unwrap();
+        assert_eq!(std::fs::read(dest.join("app.toml")).unwrap(), b"a = 1\n");
+        assert!(dest.join("empty").is_dir());
+        assert_eq!(
+            materialize(&src, &dest)
+                .atomic_swap(atomic_swap)
+                .apply()
+                .unwrap(),
+            []
+        );
+
+        std::fs::write(dest.join("extra.txt"), b"keep me").unwrap();
+        src.write_file("/app.toml".as_ref(), b"a = 2\n").unwrap();
+        src.remove_dir_all("/conf.d".as_ref()).unwrap();
+        src.write_file("/conf.d".as_ref(), b"").unwrap();
+
+        assert_eq!(
+            materialize(&src, &dest)
+                .atomic_swap(atomic_swap)
+                .apply()
+                .unwrap(),
+            [
+                Change::Update {
+                    path: "app.toml".into(),
+                    ty: FileType::File
+                },
+                Change::Update {
+                    path: "conf.d".into(),
+                    ty: FileType::File
+                },
+                Change::Delete {
+                    path: "conf.d/db.toml".into(),
+                    ty: FileType::File
+                },
+            ]
+        );
+        assert_eq!(std::fs::read(dest.join("app.toml")).unwrap(), b"a = 2\n");
+        assert!(dest.join("conf.d").is_file());
+        assert_eq!(std::fs::read(dest.join("extra.txt")).unwrap(), b"keep me");
+
+        assert_eq!(
+            materialize(&src, &dest)
+                .atomic_swap(atomic_swap)
+                .delete_extra(true)
+                .plan()
+                .unwrap(),
+            [Change::Delete {
+                path: "extra.txt".into(),
+                ty: FileType::File
+            }]
+        );
+        materialize(&src, &dest)
+            .atomic_swap(atomic_swap)
+            .delete_extra(true)
+            .apply()
+            .unwrap();
+        assert!(!dest.join("extra.txt").exists());
+
+        assert_eq!(
+            std::fs::read_dir(tmp.path()).unwrap().count(),
+            1,
+            "Staging directories are cleaned up"
+        );
+        assert_eq!(
+            std::fs::read_dir(&dest).unwrap().count(),
+            3,
+            "Temporary files are cleaned up"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_dest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let real = tmp.path().join("dotfiles/app");
+        let dest = tmp.path().join("app");
+        std::fs::create_dir_all(&real).unwrap();
+        std::os::unix::fs::symlink(&real, &dest).unwrap();
+
+        let mut src = MemFs::with_files(&[("/app.toml", "a = 1\n"), ("/conf.d/db.toml", "")]);
+        materialize(&src, &dest).apply().unwrap();
+        let anchored = RelFs::open_anchored(&dest).unwrap();
+
+        src.write_file("/app.toml".as_ref(), b"a = 2\n").unwrap();
+        src.remove_file("/conf.d/db.toml".as_ref()).unwrap();
+        materialize(&src, &dest).delete_extra(true).apply().unwrap();
+
+        assert!(dest.is_symlink());
+        assert_eq!(std::fs::read(real.join("app.toml")).unwrap(), b"a = 2\n");
+        assert!(!real.join("conf.d/db.toml").exists());
+        assert_eq!(
+            anchored.read_to_string("app.toml".as_ref()).unwrap(),
+            "a = 2\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn modes_are_kept() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = |path: PathBuf| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        let set_mode = |path: PathBuf, mode| {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).unwrap()
+        };
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dest = tmp.path().join("out");
+
+        let mut src = MemFs::with_files(&[("/conf.d/db.toml", "url = 1\n"), ("/app.toml", "")]);
+        materialize(&src, &dest).apply().unwrap();
+        set_mode(dest.clone(), 0o700);
+        set_mode(dest.join("conf.d"), 0o750);
+        set_mode(dest.join("conf.d/db.toml"), 0o600);
+
+        src.write_file("/conf.d/db.toml".as_ref(), b"url = 2\n")
+            .unwrap();
+        src.write_file("/conf.d/new.toml".as_ref(), b"").unwrap();
+        materialize(&src, &dest).apply().unwrap();
+
+        assert_eq!(
+            std::fs::read(dest.join("conf.d/db.toml")).unwrap(),
+            b"url = 2\n"
+        );
+        assert_eq!(mode(dest.clone()), 0o700);
+        assert_eq!(mode(dest.join("conf.d")), 0o750);
+        assert_eq!(mode(dest.join("conf.d/db.toml")), 0o600);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unchanged_files_are_kept() {
+        use std::os::unix::fs::MetadataExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dest = tmp.path().join("out");
+
+        let mut src = MemFs::with_files(&[("/a.toml", "a = 1\n"), ("/b.toml", "b = 1\n")]);
+        src.create_dir("/sub".as_ref()).unwrap();
+        materialize(&src, &dest).apply().unwrap();
+        std::os::unix::fs::symlink("a.toml", dest.join("link")).unwrap();
+        let inode = std::fs::metadata(dest.join("a.toml")).unwrap().ino();
+
+        src.write_file("/b.toml".as_ref(), b"b = 2\n").unwrap();
+        materialize(&src, &dest).apply().unwrap();
+        assert_eq!(std::fs::metadata(dest.join("a.toml")).unwrap().ino(), inode);
+        assert_eq!(std::fs::read(dest.join("b.toml")).unwrap(), b"b = 2\n");
+        assert_eq!(
+            std::fs::read_link(dest.join("link")).unwrap(),
+            Path::new("a.toml")
+        );
+    }
+
+    #[test]
+    fn source_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = MemFs::with_files(&[("/etc/app/app.toml", "a = 1\n"), ("/other", "")]);
+
+        materialize(&src, tmp.path())
+            .root("/etc/app")
+            .apply()
+            .unwrap();
+        assert_eq!(
+            std::fs::read(tmp.path().join("app.toml")).unwrap(),
+            b"a = 1\n"
+        );
+        assert!(!tmp.path().join("other").exists());
+
+        let file = tmp.path().join("app.toml");
+        assert_eq!(
+            materialize(&src, &file)
+                .plan()
+                .unwrap_err()
+                .without_source(),
+            Error::NotADirectory {
+                path: file,
+                source: None
+            }
+        );
+    }
+}
@@ -1,13 +1,21 @@
 use std::{
-    collections::BTreeMap,
+    cmp::Ordering,
+    collections::{BTreeMap, VecDeque},
     ffi::OsString,
     fmt,
-    io::Cursor,
+    hash::{Hash, Hasher},
+    io::{self, Cursor, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
 };
 
-use crate::{utils::PathUtil, Error, LogixVfs, LogixVfsDirEntry};
+use crate::{
+    glob::Pattern,
+    utils::{push_parts, Part, MAX_SYMLINKS},
+    walk::{walk, WalkEntry},
+    Error, FileType, LogixVfs, LogixVfsDirEntry, LogixVfsMut, Metadata, RelFs,
+};
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum FileData {
@@ -15,6 +23,15 @@ enum FileData {
     Arc(Arc<[u8]>),
 }
 
+impl FileData {
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Static(buf) => buf,
+            Self::Arc(buf) => buf,
+        }
+    }
+}
+
 impl fmt::Debug for FileData {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -24,26 +41,452 @@ impl fmt::Debug for FileData {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+type Ino = u64;
+
+const READ: u32 = 0o4;
+const WRITE: u32 = 0o2;
+const EXEC: u32 = 0o1;
+
+/// The permissions, the owner and the timestamps of a file or directory
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+struct Attrs {
+    mode: u32,
+    uid: u32,
+    gid: u32,
+    created: SystemTime,
+    modified: SystemTime,
+    accessed: SystemTime,
+    changed: SystemTime,
+}
+
+impl Attrs {
+    /// Update the timestamps after a change, `content` is set if the
+    /// content changed and not only the metadata
+    fn touch(&mut self, now: SystemTime, content: bool) {
+        self.changed = now;
+        if content {
+            self.modified = now;
+            self.accessed = now;
+        }
+    }
+}
+
+/// A file shared by all of its hard links
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+struct Inode {
+    data: FileData,
+    attrs: Attrs,
+    nlink: u64,
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 enum Entry {
+    File(Ino),
+    Dir(Attrs, BTreeMap<OsString, Entry>),
+    Symlink(PathBuf),
+}
+
+/// The user performing the operations on a [MemFs], which decides what the
+/// permission checks allow and who owns new files and directories
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct User {
+    uid: u32,
+    gid: u32,
+    groups: Vec<u32>,
+}
+
+impl User {
+    pub fn new(uid: u32, gid: u32) -> Self {
+        Self {
+            uid,
+            gid,
+            groups: Vec::new(),
+        }
+    }
+
+    /// The superuser, which passes all permission checks
+    pub fn root() -> Self {
+        Self::new(0, 0)
+    }
+
+    /// Supplementary groups the user is a member of besides the primary one
+    pub fn with_groups(mut self, groups: impl IntoIterator<Item = u32>) -> Self {
+        self.groups.extend(groups);
+        self
+    }
+
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    pub fn gid(&self) -> u32 {
+        self.gid
+    }
+
+    pub fn groups(&self) -> &[u32] {
+        &self.groups
+    }
+
+    fn in_group(&self, gid: u32) -> bool {
+        self.gid == gid || self.groups.contains(&gid)
+    }
+
+    /// Check `access` against the mode bits the same way as POSIX, only the
+    /// class the user belongs to is considered
+    fn allows(&self, attrs: &Attrs, access: u32) -> bool {
+        if self.uid == 0 {
+            return true;
+        }
+        let bits = if self.uid == attrs.uid {
+            attrs.mode >> 6
+        } else if self.in_group(attrs.gid) {
+            attrs.mode >> 3
+        } else {
+            attrs.mode
+        };
+        bits & access == access
+    }
+}
+
+impl Default for User {
+    fn default() -> Self {
+        Self::root()
+    }
+}
+
+/// The time source for the timestamps of a [MemFs]
+#[derive(Clone, Default, Debug)]
+pub enum Clock {
+    /// The current system time
     #[default]
-    Empty,
-    File(FileData),
-    Dir(BTreeMap<OsString, Entry>),
+    System,
+    /// A clock that only moves when told to, for testing time based logic
+    Manual(ManualClock),
+}
+
+impl Clock {
+    pub fn now(&self) -> SystemTime {
+        match self {
+            Self::System => SystemTime::now(),
+            Self::Manual(clock) => clock.now(),
+        }
+    }
+}
+
+impl From<ManualClock> for Clock {
+    fn from(clock: ManualClock) -> Self {
+        Self::Manual(clock)
+    }
+}
+
+/// A clock that is set and advanced by hand, clones share the same time so
+/// a test can keep one to control the time seen by a [MemFs]
+#[derive(Clone, Debug)]
+pub struct ManualClock {
+    now: Arc<Mutex<SystemTime>>,
+}
+
+impl ManualClock {
+    pub fn new(start: SystemTime) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(start)),
+        }
+    }
+
+    pub fn now(&self) -> SystemTime {
+        *self.lock()
+    }
+
+    pub fn set(&self, time: SystemTime) {
+        *self.lock() = time;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.lock() += by;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SystemTime> {
+        // NOTE(2026.10): The time is always valid, so a panic while holding the lock doesn't matter
+        self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for ManualClock {
+    /// Starts at the unix epoch
+    fn default() -> Self {
+        Self::new(SystemTime::UNIX_EPOCH)
+    }
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+/// A file system kept in memory. Entries have unix permissions and owners
+/// that are checked against the acting [User], which is root by default
+/// so nothing is denied until [MemFs::set_user] is called.
+///
+/// The timestamps are taken from a [Clock] and updated by every mutation.
+/// Reading takes `&self`, so the access time follows the modification time
+/// like on a file system mounted with `noatime`.
+#[derive(Clone, Debug)]
 pub struct MemFs {
     root: Entry,
+    inodes: BTreeMap<Ino, Inode>,
+    user: User,
+    clock: Clock,
+}
+
+/// An entry of [MemFs::key], files are numbered in the order they are first
+/// seen instead of by inode. The mode, the uid and the gid are compared.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum EntryKey<'a> {
+    File(usize, (u32, u32, u32), &'a FileData),
+    Dir((u32, u32, u32)),
+    Symlink(&'a Path),
+}
+
+impl Default for MemFs {
+    fn default() -> Self {
+        Self::with_clock(Clock::System)
+    }
+}
+
+impl PartialEq for MemFs {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for MemFs {}
+
+impl PartialOrd for MemFs {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MemFs {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl Hash for MemFs {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
+
+/// Options for [MemFs::from_vfs_with] and [MemFs::load_dir_with]
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct LoadOptions {
+    filter: Option<Pattern>,
+    max_size: Option<u64>,
+}
+
+impl LoadOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only load the files and directories matching the pattern, relative
+    /// patterns are matched against the path relative to the root. The
+    /// parent directories of matching files are always created.
+    pub fn filter(mut self, pattern: Pattern) -> Self {
+        self.filter = Some(pattern);
+        self
+    }
+
+    /// Fail with [Error::SizeLimitExceeded] if the loaded files add up to
+    /// more than `bytes`
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    fn includes(&self, rel: &Path, full: &Path) -> bool {
+        match &self.filter {
+            Some(pattern) => pattern.matches(rel) || pattern.matches(full),
+            None => true,
+        }
+    }
+}
+
+/// What happened while loading a [MemFs] from another file system
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct LoadReport {
+    skipped: Vec<WalkEntry>,
+    total_size: u64,
+}
+
+impl LoadReport {
+    /// The entries that could not be loaded because MemFs has no way to
+    /// represent them, such as special files and symbolic links with an
+    /// absolute target
+    pub fn skipped(&self) -> &[WalkEntry] {
+        &self.skipped
+    }
+
+    /// The number of bytes loaded
+    pub fn total_size(&self) -> u64 {
+        self.total_size
+    }
 }
 
 impl MemFs {
-    fn resolve_node_mut(&mut self, path: PathBuf, create_path: bool) -> Result<&mut Entry, Error> {
+    /// Copy the tree below `root` in `vfs` into a new file system, `root`
+    /// becomes the root of the returned file system
+    pub fn from_vfs(
+        vfs: &impl LogixVfs,
+        root: impl AsRef<Path>,
+    ) -> Result<(Self, LoadReport), Error> {
+        Self::from_vfs_with(vfs, root, &LoadOptions::default())
+    }
+
+    /// Like [MemFs::from_vfs] but filtered and limited by `options`
+    pub fn from_vfs_with(
+        vfs: &impl LogixVfs,
+        root: impl AsRef<Path>,
+        options: &LoadOptions,
+    ) -> Result<(Self, LoadReport), Error> {
+        let root = root.as_ref();
+        if !vfs.metadata(root)?.is_dir() {
+            return Err(Error::NotADirectory {
+                path: root.to_path_buf(),
+                source: None,
+            });
+        }
+
+        let mut ret = Self::default();
+        let mut report = LoadReport::default();
+
+        for entry in walk(vfs, root).min_depth(1) {
+            let entry = entry?;
+            let rel = entry
+                .path()
+                .strip_prefix(root)
+                .expect("Walked paths are below the root");
+            let full = Path::new("/").join(rel);
+            if !options.includes(rel, &full) {
+                continue;
+            }
+
+            match entry.file_type() {
+                FileType::Dir => ret.create_dir_all(&full)?,
+                FileType::File => {
+                    let check_size = |size: u64| match options.max_size {
+                        Some(limit) if size > limit => Err(Error::SizeLimitExceeded {
+                            path: entry.path().to_path_buf(),
+                            limit,
+                        }),
+                        _ => Ok(()),
+                    };
+                    // NOTE(2026.10): Check before reading so a huge file is never loaded
+                    if options.max_size.is_some() {
+                        check_size(report.total_size + vfs.metadata(entry.path())?.len())?;
+                    }
+                    let data = vfs.read(entry.path())?;
+                    report.total_size += data.len() as u64;
+                    check_size(report.total_size)?;
+                    if let Some(parent) = full.parent() {
+                        ret.create_dir_all(parent)?;
+                    }
+                    ret.write_file(&full, &data)?;
+                }
+                FileType::Symlink => {
+                    let target = vfs.read_link(entry.path())?;
+                    // NOTE(2026.10): An absolute target points outside of the loaded tree
+                    if target.has_root() {
+                        report.skipped.push(entry);
+                        continue;
+                    }
+                    if let Some(parent) = full.parent() {
+                        ret.create_dir_all(parent)?;
+                    }
+                    ret.symlink(target, &full)?;
+                }
+                FileType::Other => report.skipped.push(entry),
+            }
+        }
+
+        Ok((ret, report))
+    }
+
+    /// Load a directory on disk, symbolic links are not followed
+    pub fn load_dir(path: impl Into<PathBuf>) -> Result<(Self, LoadReport), Error> {
+        Self::load_dir_with(path, &LoadOptions::default())
+    }
+
+    /// Like [MemFs::load_dir] but filtered and limited by `options`
+    pub fn load_dir_with(
+        path: impl Into<PathBuf>,
+        options: &LoadOptions,
+    ) -> Result<(Self, LoadReport), Error> {
+        Self::from_vfs_with(&RelFs::new(path), "/", options)
+    }
+
+    /// The content of the tree without the inode numbers, so file systems
+    /// with the same files and hard links compare equal regardless of the
+    /// order they were created in. The acting user, the clock and the
+    /// timestamps are not part of it.
+    fn key(&self) -> Vec<(PathBuf, EntryKey<'_>)> {
+        fn visit<'a>(
+            fs: &'a MemFs,
+            path: &Path,
+            map: &'a BTreeMap<OsString, Entry>,
+            seen: &mut BTreeMap<Ino, usize>,
+            ret: &mut Vec<(PathBuf, EntryKey<'a>)>,
+        ) {
+            for (name, entry) in map {
+                let path = path.join(name);
+                match entry {
+                    Entry::File(ino) => {
+                        let next = seen.len();
+                        let id = *seen.entry(*ino).or_insert(next);
+                        let inode = fs.inode(*ino);
+                        let attrs = &inode.attrs;
+                        let perms = (attrs.mode, attrs.uid, attrs.gid);
+                        ret.push((path, EntryKey::File(id, perms, &inode.data)));
+                    }
+                    Entry::Dir(attrs, map) => {
+                        let perms = (attrs.mode, attrs.uid, attrs.gid);
+                        ret.push((path.clone(), EntryKey::Dir(perms)));
+                        visit(fs, &path, map, seen, ret);
+                    }
+                    Entry::Symlink(target) => ret.push((path, EntryKey::Symlink(target))),
+                }
+            }
+        }
+
+        let mut ret = Vec::new();
+        if let Entry::Dir(_, map) = &self.root {
+            visit(self, "/".as_ref(), map, &mut BTreeMap::new(), &mut ret);
+        }
+        ret
+    }
+
+    fn root_mut(&mut self) -> &mut BTreeMap<OsString, Entry> {
+        match &mut self.root {
+            Entry::Dir(_, map) => map,
+            _ => unreachable!("The root is always a directory"),
+        }
+    }
+
+    /// Look up the parent directory of `path` for modification, returning
+    /// it together with the file name. The parent must already exist.
+    fn parent_mut(
+        &mut self,
+        path: &Path,
+    ) -> Result<(&mut BTreeMap<OsString, Entry>, OsString), Error> {
         use std::path::Component;
 
-        let mut cur = &mut self.root;
+        let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+            return Err(Error::InvalidPath {
+                path: path.to_path_buf(),
+                source: None,
+            });
+        };
+
+        let mut cur = self.root_mut();
 
-        for (i, component) in path.components().enumerate() {
+        for (i, component) in parent.components().enumerate() {
             match component {
                 Component::RootDir => (),
                 Component::Prefix(_) | Component::CurDir | Component::ParentDir => {
@@ -52,30 +495,23 @@ impl MemFs {
                         "Internal error: path {path:?} is not canonicalized",
                     )));
                 }
-                Component::Normal(name) => 'retry_cur: loop {
-                    match cur {
-                        Entry::Empty => {
-                            if create_path {
-                                *cur = Entry::Dir([(name.to_owned(), Entry::Empty)].into());
-                                continue 'retry_cur;
-                            } else {
-                                return Err(Error::NotFound { path });
-                            }
+                Component::Normal(name) => {
+                    cur = match cur.get_mut(name) {
+                        Some(Entry::Dir(_, map)) => map,
+                        Some(Entry::File(_) | Entry::Symlink(_)) => {
+                            let dir: PathBuf = path.components().take(i + 1).collect();
+                            return Err(Error::NotADirectory {
+                                path: dir,
+                                source: None,
+                            });
                         }
-                        Entry::File(_) => {
-                            let dir: PathBuf = path.components().take(i).collect();
-                            return Err(Error::Other(format!(
-                                "Cannot create directory {dir:?} as it is a file for {path:?}"
-                            )));
-                        }
-                        Entry::Dir(map) => cur = map.entry(name.to_owned()).or_default(),
-                    }
-                    break;
-                },
+                        None => return Err(Error::not_found(path)),
+                    };
+                }
             }
         }
 
-        Ok(cur)
+        Ok((cur, name.to_owned()))
     }
 
     fn resolve_node(&self, path: PathBuf) -> Result<(PathBuf, &Entry), Error> {
@@ -93,16 +529,18 @@ impl MemFs {
                     )));
                 }
                 Component::Normal(name) => match cur {
-                    Entry::Empty => return Err(Error::NotFound { path }),
-                    Entry::File(_) => {
+                    Entry::File(_) | Entry::Symlink(_) => {
                         let dir: PathBuf = path.components().take(i).collect();
-                        return Err(Error::NotADirectory { path: dir });
+                        return Err(Error::NotADirectory {
+                            path: dir,
+                            source: None,
+                        });
                     }
-                    Entry::Dir(map) => {
+                    Entry::Dir(_, map) => {
                         if let Some(entry) = map.get(name) {
                             cur = entry;
                         } else {
-                            return Err(Error::NotFound { path });
+                            return Err(Error::not_found(path));
                         }
                     }
                 },
@@ -112,239 +550,1842 @@ impl MemFs {
         Ok((path, cur))
     }
 
-    fn resolve_path(&self, path: impl AsRef<Path>) -> Result<PathBuf, Error> {
-        PathUtil {
-            root: "/".as_ref(),
-            cur_dir: "/".as_ref(),
-        }
-        .resolve_path(false, path.as_ref())
+    fn get_mut(&mut self, path: &Path) -> Option<&mut Entry> {
+        path.iter()
+            .skip(1)
+            .try_fold(&mut self.root, |cur, name| match cur {
+                Entry::Dir(_, map) => map.get_mut(name),
+                _ => None,
+            })
     }
 
-    pub fn set_static_file(
-        &mut self,
-        path: impl AsRef<Path>,
-        data: &'static [u8],
-        create_dir: bool,
-    ) -> Result<(), Error> {
-        let path = path.as_ref();
-        let node = self.resolve_node_mut(self.resolve_path(path)?, create_dir)?;
-        match node {
-            Entry::Empty | Entry::File(_) => {
-                *node = Entry::File(FileData::Static(data));
-                Ok(())
-            }
-            Entry::Dir(_) => Err(Error::Other(format!(
-                "Can't overwrite directory with a file at {path:?}"
-            ))),
-        }
+    /// Look up a canonical path without following symbolic links
+    fn get(&self, path: &Path) -> Option<&Entry> {
+        path.iter()
+            .skip(1)
+            .try_fold(&self.root, |cur, name| match cur {
+                Entry::Dir(_, map) => map.get(name),
+                _ => None,
+            })
     }
 
-    pub fn set_file(
-        &mut self,
-        path: impl AsRef<Path>,
-        data: impl Into<Arc<[u8]>>,
-        create_dir: bool,
-    ) -> Result<(), Error> {
-        let path = path.as_ref();
-        let node = self.resolve_node_mut(self.resolve_path(path)?, create_dir)?;
-        match node {
-            Entry::Empty | Entry::File(_) => {
-                *node = Entry::File(FileData::Arc(data.into()));
-                Ok(())
+    /// Resolve `path` to a canonical path, following symbolic links one
+    /// component at a time the same way as [RelFs]. The last component is
+    /// only followed if `follow_last` is set, and the part of the path that
+    /// does not exist is resolved lexically.
+    fn resolve(&self, path: &Path, follow_last: bool) -> Result<PathBuf, Error> {
+        // NOTE(2026.10): `..` is applied after the links before it are followed, like POSIX
+        let mut pending = VecDeque::new();
+        push_parts(&mut pending, path, path)?;
+
+        let mut ret = PathBuf::from("/");
+        let mut followed = 0;
+        let mut missing = false;
+
+        while let Some(part) = pending.pop_front() {
+            match part {
+                Part::Parent => {
+                    if !ret.pop() {
+                        return Err(Error::PathOutsideBounds {
+                            path: path.to_path_buf(),
+                        });
+                    }
+                    // NOTE(2026.10): Going back up may leave the missing part, check the rest again
+                    missing = false;
+                    continue;
+                }
+                Part::Name(name) => {
+                    // NOTE(2026.10): A lookup needs search permission even if the entry is missing
+                    if let (false, Some(Entry::Dir(attrs, _))) = (missing, self.get(&ret)) {
+                        if !self.user.allows(attrs, EXEC) {
+                            return Err(Error::AccessDenied {
+                                path: path.to_path_buf(),
+                                source: None,
+                            });
+                        }
+                    }
+                    ret.push(name)
+                }
             }
-            Entry::Dir(_) => Err(Error::Other(format!(
-                "Can't overwrite directory with a file at {path:?}"
-            ))),
-        }
-    }
-}
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
-pub struct MemFileData(FileData);
+            if missing || (pending.is_empty() && !follow_last) {
+                continue;
+            }
 
-impl AsRef<[u8]> for MemFileData {
-    fn as_ref(&self) -> &[u8] {
-        match &self.0 {
-            FileData::Static(buf) => buf,
-            FileData::Arc(buf) => buf,
-        }
-    }
-}
+            let target = match self.get(&ret) {
+                Some(Entry::Symlink(target)) => target,
+                Some(_) => continue,
+                None => {
+                    missing = true;
+                    continue;
+                }
+            };
 
-pub type MemFile = Cursor<MemFileData>;
+            followed += 1;
+            if followed > MAX_SYMLINKS {
+                return Err(Error::TooManySymlinks {
+                    path: path.to_path_buf(),
+                    source: None,
+                });
+            }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
-enum DirEntryType {
-    File,
-    Dir,
-}
+            ret.pop();
+            if target.has_root() {
+                ret = PathBuf::from("/");
+            }
+            push_parts(&mut pending, target, path)?;
+        }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
-pub struct DirEntry {
-    path: PathBuf,
-    ty: DirEntryType,
-}
+        Ok(ret)
+    }
 
-impl LogixVfsDirEntry for DirEntry {
-    fn path(&self) -> &Path {
-        &self.path
+    fn new_attrs(&self, mode: u32) -> Attrs {
+        let now = self.clock.now();
+        Attrs {
+            mode,
+            uid: self.user.uid,
+            gid: self.user.gid,
+            created: now,
+            modified: now,
+            accessed: now,
+            changed: now,
+        }
     }
 
-    fn is_dir(&self) -> bool {
-        match self.ty {
-            DirEntryType::File => false,
-            DirEntryType::Dir => true,
+    /// Update the timestamps of the entry at the canonical `full_path`, see
+    /// [Attrs::touch]
+    fn touch(&mut self, full_path: &Path, content: bool) {
+        let now = self.clock.now();
+        let ino = match self.get(full_path) {
+            Some(Entry::File(ino)) => Some(*ino),
+            Some(Entry::Dir(..)) => None,
+            Some(Entry::Symlink(_)) | None => return,
+        };
+        let attrs = match ino {
+            Some(ino) => &mut self.inode_mut(ino).attrs,
+            None => match self.get_mut(full_path) {
+                Some(Entry::Dir(attrs, _)) => attrs,
+                _ => unreachable!("Checked above"),
+            },
+        };
+        attrs.touch(now, content);
+    }
+
+    /// Update the timestamps of the directory containing `full_path` after
+    /// an entry was added or removed
+    fn touch_parent(&mut self, full_path: &Path) {
+        if let Some(parent) = full_path.parent() {
+            self.touch(parent, true);
         }
     }
 
-    fn is_file(&self) -> bool {
-        match self.ty {
-            DirEntryType::File => true,
-            DirEntryType::Dir => false,
+    fn attrs<'a>(&'a self, entry: &'a Entry) -> Option<&'a Attrs> {
+        match entry {
+            Entry::File(ino) => Some(&self.inode(*ino).attrs),
+            Entry::Dir(attrs, _) => Some(attrs),
+            Entry::Symlink(_) => None,
         }
     }
 
-    fn is_symlink(&self) -> bool {
-        false
+    /// Fail with [Error::AccessDenied] unless the acting user has `access`
+    /// to the entry at the canonical `full_path`, a missing entry is left for
+    /// the operation to report
+    fn check_access(&self, full_path: &Path, access: u32, path: &Path) -> Result<(), Error> {
+        match self.get(full_path).and_then(|entry| self.attrs(entry)) {
+            Some(attrs) if !self.user.allows(attrs, access) => Err(Error::AccessDenied {
+                path: path.to_path_buf(),
+                source: None,
+            }),
+            _ => Ok(()),
+        }
     }
-}
 
-#[derive(Clone, Debug)]
-pub struct ReadDir {
-    it: std::vec::IntoIter<DirEntry>,
-}
+    /// Check that the entry at `full_path` can be added to or removed from
+    /// its directory
+    fn check_parent(&self, full_path: &Path, path: &Path) -> Result<(), Error> {
+        match full_path.parent() {
+            Some(parent) => self.check_access(parent, WRITE | EXEC, path),
+            None => Ok(()),
+        }
+    }
 
-impl ReadDir {
-    fn new(base: &Path, map: &BTreeMap<OsString, Entry>) -> Self {
-        let list: Vec<_> = map
-            .iter()
-            .filter_map(|(k, v)| {
-                let ty = match v {
-                    Entry::Empty => return None,
-                    Entry::File(_) => DirEntryType::File,
-                    Entry::Dir(_) => DirEntryType::Dir,
-                };
+    /// Check that everything below a directory can be removed, `path` is the
+    /// path of `entry` reported in errors
+    fn check_remove_all(&self, entry: &Entry, path: &Path) -> Result<(), Error> {
+        match entry {
+            Entry::Dir(attrs, map) if !map.is_empty() => {
+                if !self.user.allows(attrs, READ | WRITE | EXEC) {
+                    return Err(Error::AccessDenied {
+                        path: path.to_path_buf(),
+                        source: None,
+                    });
+                }
+                map.iter()
+                    .try_for_each(|(name, entry)| self.check_remove_all(entry, &path.join(name)))
+            }
+            _ => Ok(()),
+        }
+    }
 
-                Some(DirEntry {
-                    path: base.join(k),
-                    ty,
-                })
-            })
-            .collect();
-        ReadDir {
-            it: list.into_iter(),
+    /// Create an empty file system taking the timestamps from `clock`
+    pub fn with_clock(clock: impl Into<Clock>) -> Self {
+        let clock = clock.into();
+        let now = clock.now();
+        Self {
+            root: Entry::Dir(
+                Attrs {
+                    mode: 0o755,
+                    uid: 0,
+                    gid: 0,
+                    created: now,
+                    modified: now,
+                    accessed: now,
+                    changed: now,
+                },
+                BTreeMap::new(),
+            ),
+            inodes: BTreeMap::new(),
+            user: User::root(),
+            clock,
         }
     }
-}
 
-impl Iterator for ReadDir {
-    type Item = Result<DirEntry, Error>;
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.it.next().map(Ok)
+    /// The user the operations are performed as
+    pub fn user(&self) -> &User {
+        &self.user
     }
-}
 
-impl LogixVfs for MemFs {
-    type RoFile = Cursor<MemFileData>;
-    type DirEntry = DirEntry;
-    type ReadDir = ReadDir;
+    /// Perform the following operations as `user`, the existing entries keep
+    /// their owners
+    pub fn set_user(&mut self, user: User) {
+        self.user = user;
+    }
 
-    fn canonicalize_path(&self, path: &Path) -> Result<PathBuf, crate::Error> {
-        self.resolve_path(path)
+    /// Change the permission bits of a file or directory like `chmod`, only
+    /// allowed for the owner and root
+    pub fn set_permissions(&mut self, path: impl AsRef<Path>, mode: u32) -> Result<(), Error> {
+        self.change_attrs(path.as_ref(), |user, attrs| {
+            if user.uid != 0 && user.uid != attrs.uid {
+                return false;
+            }
+            attrs.mode = mode & 0o7777;
+            true
+        })
     }
 
-    fn open_file(&self, path: &Path) -> Result<Self::RoFile, crate::Error> {
-        match self.resolve_node(self.resolve_path(path)?)? {
-            (_, Entry::Empty) => Err(Error::NotFound {
-                path: path.to_path_buf(),
-            }),
-            (_, Entry::File(data)) => Ok(Cursor::new(MemFileData(data.clone()))),
-            (_, Entry::Dir(_)) => Err(Error::Other(format!("The path {path:?} is not a file"))),
-        }
+    /// Change the owner of a file or directory like `chown`. Only root can
+    /// give an entry away, the owner can only change the group to one of
+    /// their own groups.
+    pub fn set_owner(&mut self, path: impl AsRef<Path>, uid: u32, gid: u32) -> Result<(), Error> {
+        self.change_attrs(path.as_ref(), |user, attrs| {
+            if user.uid != 0 && (user.uid != attrs.uid || uid != attrs.uid || !user.in_group(gid)) {
+                return false;
+            }
+            attrs.uid = uid;
+            attrs.gid = gid;
+            true
+        })
+    }
+
+    fn change_attrs(
+        &mut self,
+        path: &Path,
+        f: impl FnOnce(&User, &mut Attrs) -> bool,
+    ) -> Result<(), Error> {
+        let full_path = self.resolve(path, true)?;
+        let user = self.user.clone();
+        let now = self.clock.now();
+        let attrs = match self.resolve_node(full_path.clone())? {
+            (_, Entry::File(ino)) => {
+                let ino = *ino;
+                &mut self.inode_mut(ino).attrs
+            }
+            _ if full_path.parent().is_none() => match &mut self.root {
+                Entry::Dir(attrs, _) => attrs,
+                _ => unreachable!("The root is always a directory"),
+            },
+            _ => {
+                let (dir, name) = self.parent_mut(&full_path)?;
+                match dir.get_mut(&name) {
+                    Some(Entry::Dir(attrs, _)) => attrs,
+                    _ => unreachable!("Resolved above, symbolic links are followed"),
+                }
+            }
+        };
+
+        if f(&user, attrs) {
+            attrs.touch(now, false);
+            Ok(())
+        } else {
+            Err(Error::AccessDenied {
+                path: path.to_path_buf(),
+                source: None,
+            })
+        }
+    }
+
+    fn metadata_with_attrs(file_type: FileType, len: u64, attrs: &Attrs) -> Metadata {
+        let type_bits = match file_type {
+            FileType::Dir => 0o040000,
+            _ => 0o100000,
+        };
+        Metadata {
+            readonly: attrs.mode & 0o222 == 0,
+            mode: Some(type_bits | attrs.mode),
+            uid: Some(attrs.uid),
+            gid: Some(attrs.gid),
+            created: Some(attrs.created),
+            modified: Some(attrs.modified),
+            accessed: Some(attrs.accessed),
+            changed: Some(attrs.changed),
+            ..Metadata::new(file_type, len)
+        }
+    }
+
+    fn inode(&self, ino: Ino) -> &Inode {
+        self.inodes.get(&ino).expect("Every file has an inode")
+    }
+
+    fn inode_mut(&mut self, ino: Ino) -> &mut Inode {
+        self.inodes.get_mut(&ino).expect("Every file has an inode")
+    }
+
+    fn add_inode(&mut self, data: FileData) -> Ino {
+        let ino = self.inodes.last_key_value().map_or(1, |(ino, _)| ino + 1);
+        let attrs = self.new_attrs(0o644);
+        self.inodes.insert(
+            ino,
+            Inode {
+                data,
+                attrs,
+                nlink: 1,
+            },
+        );
+        ino
+    }
+
+    /// Drop the links held by an entry that was removed from the tree, the
+    /// inodes without any links left are freed
+    fn release(&mut self, entry: Entry) {
+        match entry {
+            Entry::File(ino) => {
+                let now = self.clock.now();
+                let inode = self.inode_mut(ino);
+                inode.nlink -= 1;
+                inode.attrs.touch(now, false);
+                if inode.nlink == 0 {
+                    self.inodes.remove(&ino);
+                }
+            }
+            Entry::Dir(_, map) => map.into_values().for_each(|entry| self.release(entry)),
+            Entry::Symlink(_) => {}
+        }
+    }
+
+    /// Replace the content of a file, which is seen through all of its hard
+    /// links, or create a new file
+    fn set_file_data(&mut self, path: &Path, data: FileData) -> Result<(), Error> {
+        let full_path = self.resolve(path, true)?;
+        let (dir, name) = self.parent_mut(&full_path)?;
+        match dir.get(&name) {
+            Some(Entry::Dir(..)) => Err(Error::IsADirectory {
+                path: path.to_path_buf(),
+                source: None,
+            }),
+            Some(Entry::File(ino)) => {
+                let ino = *ino;
+                self.check_access(&full_path, WRITE, path)?;
+                self.inode_mut(ino).data = data;
+                self.touch(&full_path, true);
+                Ok(())
+            }
+            Some(Entry::Symlink(_)) | None => {
+                self.check_parent(&full_path, path)?;
+                let ino = self.add_inode(data);
+                self.parent_mut(&full_path)?
+                    .0
+                    .insert(name, Entry::File(ino));
+                self.touch_parent(&full_path);
+                Ok(())
+            }
+        }
+    }
+
+    /// Create a symbolic link at `link` pointing to `target`. The target is
+    /// stored as is and does not have to exist, relative targets are resolved
+    /// from the directory containing the link.
+    pub fn symlink(
+        &mut self,
+        target: impl AsRef<Path>,
+        link: impl AsRef<Path>,
+    ) -> Result<(), Error> {
+        let link = link.as_ref();
+        let full_path = self.resolve(link, false)?;
+        let (dir, name) = self.parent_mut(&full_path)?;
+        if dir.contains_key(&name) {
+            return Err(Error::AlreadyExists {
+                path: link.to_path_buf(),
+                source: None,
+            });
+        }
+        self.check_parent(&full_path, link)?;
+        self.parent_mut(&full_path)?
+            .0
+            .insert(name, Entry::Symlink(target.as_ref().to_path_buf()));
+        self.touch_parent(&full_path);
+        Ok(())
+    }
+
+    /// Create a hard link at `link` to the file `original`, after which both
+    /// paths share the content and the metadata. Symbolic links in
+    /// `original` are followed.
+    pub fn hard_link(
+        &mut self,
+        original: impl AsRef<Path>,
+        link: impl AsRef<Path>,
+    ) -> Result<(), Error> {
+        let (original, link) = (original.as_ref(), link.as_ref());
+        let ino = match self.resolve_node(self.resolve(original, true)?)? {
+            (_, Entry::File(ino)) => *ino,
+            _ => {
+                return Err(Error::NotAFile {
+                    path: original.to_path_buf(),
+                })
+            }
+        };
+
+        let full_path = self.resolve(link, false)?;
+        let (dir, name) = self.parent_mut(&full_path)?;
+        if dir.contains_key(&name) {
+            return Err(Error::AlreadyExists {
+                path: link.to_path_buf(),
+                source: None,
+            });
+        }
+        self.check_parent(&full_path, link)?;
+        self.parent_mut(&full_path)?
+            .0
+            .insert(name, Entry::File(ino));
+        self.inode_mut(ino).nlink += 1;
+        self.touch(&full_path, false);
+        self.touch_parent(&full_path);
+        Ok(())
+    }
+
+    /// Create or replace a file without copying the data, useful for
+    /// embedding files using `include_bytes!`
+    pub fn write_static_file(
+        &mut self,
+        path: impl AsRef<Path>,
+        data: &'static [u8],
+    ) -> Result<(), Error> {
+        self.set_file_data(path.as_ref(), FileData::Static(data))
+    }
+
+    /// Create or truncate a file and return a handle for writing to it, see
+    /// [MemFs::create_file_with]
+    pub fn create_file(&mut self, path: impl AsRef<Path>) -> Result<MemFileWriter<'_>, Error> {
+        self.create_file_with(path, WriteMode::Truncate)
+    }
+
+    /// Return a handle for writing to a file, creating it if needed. The
+    /// written data is published when the handle is dropped or by
+    /// [MemFileWriter::commit], until then the file keeps its old content.
+    pub fn create_file_with(
+        &mut self,
+        path: impl AsRef<Path>,
+        mode: WriteMode,
+    ) -> Result<MemFileWriter<'_>, Error> {
+        let path = path.as_ref();
+        let full_path = self.resolve(path, true)?;
+        let (dir, name) = self.parent_mut(&full_path)?;
+        let append_to = match dir.get(&name) {
+            Some(Entry::Dir(..)) => {
+                return Err(Error::IsADirectory {
+                    path: path.to_path_buf(),
+                    source: None,
+                })
+            }
+            Some(Entry::File(ino)) => {
+                let ino = *ino;
+                self.check_access(&full_path, WRITE, path)?;
+                (mode == WriteMode::Append).then_some(ino)
+            }
+            Some(Entry::Symlink(_)) | None => {
+                self.check_parent(&full_path, path)?;
+                None
+            }
+        };
+        let data = append_to.map_or_else(Vec::new, |ino| self.inode(ino).data.as_bytes().to_vec());
+
+        let mut buf = Cursor::new(data);
+        buf.seek(SeekFrom::End(0))
+            .expect("Seeking a cursor never fails");
+        Ok(MemFileWriter {
+            fs: self,
+            path: full_path,
+            mode,
+            buf: Some(buf),
+        })
+    }
+}
+
+impl LogixVfsMut for MemFs {
+    fn write_file(&mut self, path: &Path, data: &[u8]) -> Result<(), Error> {
+        self.set_file_data(path, FileData::Arc(data.into()))
+    }
+
+    fn append_file(&mut self, path: &Path, data: &[u8]) -> Result<(), Error> {
+        let full_path = self.resolve(path, true)?;
+        let (dir, name) = self.parent_mut(&full_path)?;
+        match dir.get(&name) {
+            Some(Entry::Dir(..)) => Err(Error::IsADirectory {
+                path: path.to_path_buf(),
+                source: None,
+            }),
+            Some(Entry::File(ino)) => {
+                let ino = *ino;
+                self.check_access(&full_path, WRITE, path)?;
+                let old = &mut self.inode_mut(ino).data;
+                *old = FileData::Arc([old.as_bytes(), data].concat().into());
+                self.touch(&full_path, true);
+                Ok(())
+            }
+            Some(Entry::Symlink(_)) | None => {
+                self.check_parent(&full_path, path)?;
+                let ino = self.add_inode(FileData::Arc(data.into()));
+                self.parent_mut(&full_path)?
+                    .0
+                    .insert(name, Entry::File(ino));
+                self.touch_parent(&full_path);
+                Ok(())
+            }
+        }
+    }
+
+    fn create_dir(&mut self, path: &Path) -> Result<(), Error> {
+        let full_path = self.resolve(path, false)?;
+        let (dir, name) = self.parent_mut(&full_path)?;
+        if dir.contains_key(&name) {
+            return Err(Error::AlreadyExists {
+                path: path.to_path_buf(),
+                source: None,
+            });
+        }
+        self.check_parent(&full_path, path)?;
+        let attrs = self.new_attrs(0o755);
+        self.parent_mut(&full_path)?
+            .0
+            .insert(name, Entry::Dir(attrs, BTreeMap::new()));
+        self.touch_parent(&full_path);
+        Ok(())
+    }
+
+    fn create_dir_all(&mut self, path: &Path) -> Result<(), Error> {
+        use std::path::Component;
+
+        let full_path = self.resolve(path, true)?;
+
+        // NOTE(2026.10): Check the whole path first so a failure doesn't leave directories behind
+        match self.resolve_node(full_path.clone()) {
+            Ok((_, Entry::Dir(..))) => return Ok(()),
+            Ok((_, Entry::File(_) | Entry::Symlink(_))) => {
+                return Err(Error::AlreadyExists {
+                    path: path.to_path_buf(),
+                    source: None,
+                })
+            }
+            Err(Error::NotFound { .. }) => {}
+            Err(e) => return Err(e),
+        }
+        let existing = full_path
+            .ancestors()
+            .find(|dir| self.get(dir).is_some())
+            .expect("The root always exists")
+            .to_path_buf();
+        self.check_access(&existing, WRITE | EXEC, path)?;
+
+        let attrs = self.new_attrs(0o755);
+        let mut cur = self.root_mut();
+        for component in full_path.components() {
+            if let Component::Normal(name) = component {
+                cur = match cur
+                    .entry(name.to_owned())
+                    .or_insert_with(|| Entry::Dir(attrs, BTreeMap::new()))
+                {
+                    Entry::Dir(_, map) => map,
+                    _ => unreachable!("Checked above"),
+                };
+            }
+        }
+        self.touch(&existing, true);
+
+        Ok(())
+    }
+
+    fn remove_file(&mut self, path: &Path) -> Result<(), Error> {
+        let full_path = self.resolve(path, false)?;
+        let (dir, name) = self.parent_mut(&full_path)?;
+        match dir.get(&name) {
+            Some(Entry::File(_) | Entry::Symlink(_)) => {
+                self.check_parent(&full_path, path)?;
+                let (dir, _) = self.parent_mut(&full_path)?;
+                let entry = dir.remove(&name).expect("Checked above");
+                self.release(entry);
+                self.touch_parent(&full_path);
+                Ok(())
+            }
+            Some(Entry::Dir(..)) => Err(Error::IsADirectory {
+                path: path.to_path_buf(),
+                source: None,
+            }),
+            None => Err(Error::not_found(path)),
+        }
+    }
+
+    fn remove_dir(&mut self, path: &Path) -> Result<(), Error> {
+        let full_path = self.resolve(path, false)?;
+        let (dir, name) = self.parent_mut(&full_path)?;
+        match dir.get(&name) {
+            Some(Entry::Dir(_, map)) if map.is_empty() => {
+                self.check_parent(&full_path, path)?;
+                let (dir, _) = self.parent_mut(&full_path)?;
+                dir.remove(&name);
+                self.touch_parent(&full_path);
+                Ok(())
+            }
+            Some(Entry::Dir(..)) => Err(Error::DirectoryNotEmpty {
+                path: path.to_path_buf(),
+                source: None,
+            }),
+            Some(Entry::File(_) | Entry::Symlink(_)) => Err(Error::NotADirectory {
+                path: path.to_path_buf(),
+                source: None,
+            }),
+            None => Err(Error::not_found(path)),
+        }
+    }
+
+    /// A symbolic link is removed without touching its target
+    fn remove_dir_all(&mut self, path: &Path) -> Result<(), Error> {
+        let full_path = self.resolve(path, false)?;
+        let (dir, name) = self.parent_mut(&full_path)?;
+        match dir.get(&name) {
+            Some(Entry::Dir(..) | Entry::Symlink(_)) => {
+                // NOTE(2026.10): Check everything first so nothing is removed if a part is denied
+                self.check_remove_all(self.get(&full_path).expect("Checked above"), path)?;
+                self.check_parent(&full_path, path)?;
+                let (dir, _) = self.parent_mut(&full_path)?;
+                let entry = dir.remove(&name).expect("Checked above");
+                self.release(entry);
+                self.touch_parent(&full_path);
+                Ok(())
+            }
+            Some(Entry::File(_)) => Err(Error::NotADirectory {
+                path: path.to_path_buf(),
+                source: None,
+            }),
+            None => Err(Error::not_found(path)),
+        }
+    }
+
+    fn rename(&mut self, from: &Path, to: &Path) -> Result<(), Error> {
+        let full_from = self.resolve(from, false)?;
+        let full_to = self.resolve(to, false)?;
+
+        let (dir, from_name) = self.parent_mut(&full_from)?;
+        let (from_is_dir, from_ino) = match dir.get(&from_name) {
+            Some(Entry::Dir(..)) => (true, None),
+            Some(Entry::File(ino)) => (false, Some(*ino)),
+            Some(Entry::Symlink(_)) => (false, None),
+            None => return Err(Error::not_found(from)),
+        };
+
+        if full_from == full_to {
+            return Ok(());
+        }
+
+        if from_is_dir && full_to.starts_with(&full_from) {
+            return Err(Error::InvalidPath {
+                path: to.to_path_buf(),
+                source: None,
+            });
+        }
+
+        self.check_parent(&full_from, from)?;
+        self.check_parent(&full_to, to)?;
+        // NOTE(2026.10): Moving a directory elsewhere updates its `..` entry, which needs write permission
+        if from_is_dir && full_from.parent() != full_to.parent() {
+            self.check_access(&full_from, WRITE, from)?;
+        }
+
+        let (dir, to_name) = self.parent_mut(&full_to)?;
+        match dir.get(&to_name) {
+            // NOTE(2026.10): Like POSIX, renaming onto another hard link of the same file does nothing
+            Some(Entry::File(ino)) if Some(*ino) == from_ino => return Ok(()),
+            Some(Entry::Dir(..)) if !from_is_dir => {
+                return Err(Error::IsADirectory {
+                    path: to.to_path_buf(),
+                    source: None,
+                })
+            }
+            Some(Entry::Dir(_, map)) if !map.is_empty() => {
+                return Err(Error::DirectoryNotEmpty {
+                    path: to.to_path_buf(),
+                    source: None,
+                })
+            }
+            Some(Entry::File(_) | Entry::Symlink(_)) if from_is_dir => {
+                return Err(Error::NotADirectory {
+                    path: to.to_path_buf(),
+                    source: None,
+                })
+            }
+            _ => {}
+        }
+
+        let (dir, _) = self.parent_mut(&full_from)?;
+        let entry = dir.remove(&from_name).expect("Checked above");
+        let (dir, _) = self.parent_mut(&full_to)?;
+        if let Some(old) = dir.insert(to_name, entry) {
+            self.release(old);
+        }
+        self.touch_parent(&full_from);
+        self.touch_parent(&full_to);
+        self.touch(&full_to, false);
+        Ok(())
+    }
+
+    /// The data is shared between the files until one of them is replaced,
+    /// so copying is cheap even for large files. Unlike a hard link, the
+    /// copy is a separate file.
+    fn copy(&mut self, from: &Path, to: &Path) -> Result<u64, Error> {
+        let data = match self.resolve_node(self.resolve(from, true)?)? {
+            (full_path, Entry::File(ino)) => {
+                self.check_access(&full_path, READ, from)?;
+                self.inode(*ino).data.clone()
+            }
+            (_, Entry::Dir(..) | Entry::Symlink(_)) => {
+                return Err(Error::NotAFile {
+                    path: from.to_path_buf(),
+                })
+            }
+        };
+        let len = data.as_bytes().len() as u64;
+        self.set_file_data(to, data)?;
+        Ok(len)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct MemFileData(FileData);
+
+impl AsRef<[u8]> for MemFileData {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+pub type MemFile = Cursor<MemFileData>;
+
+/// How [MemFs::create_file_with] treats the existing content of a file
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum WriteMode {
+    /// Start with an empty file
+    Truncate,
+    /// Keep the existing content, all writes go to the end of the file
+    /// regardless of the position
+    Append,
+}
+
+/// A file being written, created by [MemFs::create_file]. It holds the file
+/// system borrowed, so readers only ever see the old or the new content.
+/// Files opened for reading earlier keep seeing the content they opened.
+///
+/// Finish writing with [MemFileWriter::commit] to find out if the data could
+/// be published. Dropping the writer publishes it too, but has no way to
+/// report an error.
+#[derive(Debug)]
+pub struct MemFileWriter<'a> {
+    fs: &'a mut MemFs,
+    path: PathBuf,
+    mode: WriteMode,
+    buf: Option<Cursor<Vec<u8>>>,
+}
+
+impl MemFileWriter<'_> {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Publish the written data. This is also done when dropped, but only
+    /// this reports if it failed.
+    pub fn commit(mut self) -> Result<(), Error> {
+        self.publish()
+    }
+
+    fn buf(&mut self) -> &mut Cursor<Vec<u8>> {
+        self.buf.as_mut().expect("Only taken when published")
+    }
+
+    fn publish(&mut self) -> Result<(), Error> {
+        match self.buf.take() {
+            Some(buf) => self
+                .fs
+                .set_file_data(&self.path, FileData::Arc(buf.into_inner().into())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Write for MemFileWriter<'_> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if self.mode == WriteMode::Append {
+            self.buf().seek(SeekFrom::End(0))?;
+        }
+        self.buf().write(data)
+    }
+
+    /// Does nothing, the data is published by [MemFileWriter::commit]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for MemFileWriter<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.buf().seek(pos)
+    }
+}
+
+impl Drop for MemFileWriter<'_> {
+    fn drop(&mut self) {
+        // NOTE(2026.10): The parent was checked when created and the borrow keeps it in place
+        let res = self.publish();
+        debug_assert!(
+            res.is_ok() || std::thread::panicking(),
+            "Dropping the writer for {:?} lost the data, use commit to handle errors: {res:?}",
+            self.path
+        );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+enum DirEntryType {
+    File,
+    Dir,
+    Symlink,
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct DirEntry {
+    path: PathBuf,
+    ty: DirEntryType,
+}
+
+impl LogixVfsDirEntry for DirEntry {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn is_dir(&self) -> bool {
+        self.ty == DirEntryType::Dir
+    }
+
+    fn is_file(&self) -> bool {
+        self.ty == DirEntryType::File
+    }
+
+    fn is_symlink(&self) -> bool {
+        self.ty == DirEntryType::Symlink
+    }
+}
+
+/// Iterates the entries of a directory, like [RelFs](crate::RelFs) the path
+/// of each entry is the directory path given by the caller joined with the
+/// name of the entry
+#[derive(Clone, Debug)]
+pub struct ReadDir {
+    it: std::vec::IntoIter<DirEntry>,
+}
+
+impl ReadDir {
+    fn new(base: &Path, map: &BTreeMap<OsString, Entry>) -> Self {
+        let list: Vec<_> = map
+            .iter()
+            .map(|(k, v)| DirEntry {
+                path: base.join(k),
+                ty: match v {
+                    Entry::File(_) => DirEntryType::File,
+                    Entry::Dir(..) => DirEntryType::Dir,
+                    Entry::Symlink(_) => DirEntryType::Symlink,
+                },
+            })
+            .collect();
+        ReadDir {
+            it: list.into_iter(),
+        }
+    }
+}
+
+impl Iterator for ReadDir {
+    type Item = Result<DirEntry, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.it.next().map(Ok)
+    }
+}
+
+impl LogixVfs for MemFs {
+    type RoFile = Cursor<MemFileData>;
+    type DirEntry = DirEntry;
+    type ReadDir = ReadDir;
+
+    fn canonicalize_path(&self, path: &Path) -> Result<PathBuf, crate::Error> {
+        self.resolve(path, true)
+    }
+
+    fn open_file(&self, path: &Path) -> Result<Self::RoFile, crate::Error> {
+        match self.resolve_node(self.resolve(path, true)?)? {
+            (full_path, Entry::File(ino)) => {
+                self.check_access(&full_path, READ, path)?;
+                Ok(Cursor::new(MemFileData(self.inode(*ino).data.clone())))
+            }
+            (_, Entry::Dir(..) | Entry::Symlink(_)) => Err(Error::NotAFile {
+                path: path.to_path_buf(),
+            }),
+        }
     }
 
     fn read_dir(&self, path: &Path) -> Result<Self::ReadDir, crate::Error> {
-        match self.resolve_node(self.resolve_path(path)?)? {
-            (_, Entry::Empty) => Err(Error::NotFound {
+        match self.resolve_node(self.resolve(path, true)?)? {
+            (_, Entry::File(_) | Entry::Symlink(_)) => Err(Error::NotADirectory {
+                path: path.to_path_buf(),
+                source: None,
+            }),
+            (_, Entry::Dir(attrs, map)) => {
+                if !self.user.allows(attrs, READ) {
+                    return Err(Error::AccessDenied {
+                        path: path.to_path_buf(),
+                        source: None,
+                    });
+                }
+                Ok(ReadDir::new(path, map))
+            }
+        }
+    }
+
+    fn metadata(&self, path: &Path) -> Result<Metadata, crate::Error> {
+        match self.resolve_node(self.resolve(path, true)?)? {
+            (_, Entry::File(ino)) => {
+                let inode = self.inode(*ino);
+                Ok(Metadata {
+                    nlink: Some(inode.nlink),
+                    ..Self::metadata_with_attrs(
+                        FileType::File,
+                        inode.data.as_bytes().len().try_into().unwrap(),
+                        &inode.attrs,
+                    )
+                })
+            }
+            // NOTE(2026.10): Each subdirectory links back with `..`, as on unix
+            (_, Entry::Dir(attrs, map)) => Ok(Metadata {
+                nlink: Some(
+                    2 + map.values().filter(|e| matches!(e, Entry::Dir(..))).count() as u64,
+                ),
+                ..Self::metadata_with_attrs(FileType::Dir, 0, attrs)
+            }),
+            (_, Entry::Symlink(target)) => Ok(Metadata {
+                nlink: Some(1),
+                mode: Some(0o120777),
+                ..Metadata::new(
+                    FileType::Symlink,
+                    target.as_os_str().len().try_into().unwrap(),
+                )
+            }),
+        }
+    }
+
+    fn read_link(&self, path: &Path) -> Result<PathBuf, crate::Error> {
+        match self.resolve_node(self.resolve(path, false)?)? {
+            (_, Entry::Symlink(target)) => Ok(target.clone()),
+            _ => Err(Error::InvalidPath {
                 path: path.to_path_buf(),
+                source: None,
             }),
-            (path, Entry::File(_)) => Err(Error::NotADirectory { path }),
-            (path, Entry::Dir(map)) => Ok(ReadDir::new(&path, map)),
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn suggest_paths(&self, path: &Path) -> Vec<PathBuf> {
+        crate::suggest::suggest_paths(path, |dir| {
+            match self.resolve_node(self.resolve(dir, true).ok()?).ok()? {
+                (_, Entry::Dir(attrs, map)) if self.user.allows(attrs, READ) => {
+                    Some(map.keys().cloned())
+                }
+                _ => None,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+impl MemFs {
+    /// Create a file system holding `files` as `(path, content)` pairs, any
+    /// missing parent directories are created
+    pub(crate) fn with_files(files: &[(&str, &str)]) -> Self {
+        let mut ret = Self::default();
+        for (path, data) in files {
+            let path = Path::new(path);
+            ret.create_dir_all(path.parent().unwrap()).unwrap();
+            ret.write_file(path, data.as_bytes()).unwrap();
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basics() {
+        let mut fs = MemFs::default();
+        let hello_rs = b"fn hello() -> i32 {{\n42}}\n".as_slice();
+        let world_rs = b"fn world() -> i32 {{\n1337}}\n".as_slice();
+
+        assert_eq!(
+            fs.write_static_file("/src/hello.rs", hello_rs).unwrap_err(),
+            Error::not_found("/src/hello.rs")
+        );
+
+        fs.create_dir_all("/src".as_ref()).unwrap();
+        fs.write_static_file("/src/hello.rs", hello_rs).unwrap();
+        fs.write_static_file("/src/world.rs", world_rs).unwrap();
+
+        assert_eq!(
+            fs.write_static_file("/src/hello.rs/world.rs", hello_rs)
+                .unwrap_err(),
+            Error::NotADirectory {
+                path: "/src/hello.rs".into(),
+                source: None
+            }
+        );
+
+        assert_eq!(
+            fs.create_dir_all("/src/hello.rs/world".as_ref())
+                .unwrap_err(),
+            Error::NotADirectory {
+                path: "/src/hello.rs".into(),
+                source: None
+            }
+        );
+        assert_eq!(
+            fs.create_dir_all("/src/hello.rs".as_ref()).unwrap_err(),
+            Error::AlreadyExists {
+                path: "/src/hello.rs".into(),
+                source: None
+            }
+        );
+
+        assert_eq!(
+            fs.open_file("/src/hello.rs".as_ref()).unwrap().get_ref().0,
+            FileData::Static(hello_rs)
+        );
+
+        assert_eq!(
+            fs.open_file("/src".as_ref()).unwrap_err(),
+            Error::NotAFile {
+                path: "/src".into()
+            }
+        );
+
+        assert_eq!(
+            fs.open_file("/src/hello.rs/world.rs".as_ref()).unwrap_err(),
+            Error::NotADirectory {
+                path: "/src/hello.rs".into(),
+                source: None
+            }
+        );
+
+        {
+            let meta = fs.metadata("/src/hello.rs".as_ref()).unwrap();
+            assert!(meta.is_file());
+            assert_eq!(meta.len(), hello_rs.len() as u64);
+            assert!(meta.modified().is_some());
+
+            assert!(fs.metadata("/src".as_ref()).unwrap().is_dir());
+            assert_eq!(
+                fs.metadata("/src/nope.rs".as_ref()).unwrap_err(),
+                Error::not_found("/src/nope.rs")
+            );
+        }
+
+        {
+            let mut it = fs.read_dir("/".as_ref()).unwrap();
+            let entry = it.next().unwrap().unwrap();
+            assert!(it.next().is_none());
+
+            assert_eq!(entry.path(), Path::new("/src"),);
+            assert!(entry.is_dir());
+            assert!(!entry.is_file());
+        }
+
+        {
+            let mut it = fs.read_dir("/src".as_ref()).unwrap();
+            let file1 = it.next().unwrap().unwrap();
+            let file2 = it.next().unwrap().unwrap();
+            assert!(it.next().is_none());
+
+            assert_eq!(file1.path(), Path::new("/src/hello.rs"),);
+            assert_eq!(file2.path(), Path::new("/src/world.rs"),);
+
+            assert!(!file1.is_dir());
+            assert!(!file2.is_dir());
+
+            assert!(file1.is_file());
+            assert!(file2.is_file());
+        }
+    }
+
+    fn read(fs: &MemFs, path: &str) -> Vec<u8> {
+        fs.open_file(path.as_ref())
+            .unwrap()
+            .into_inner()
+            .as_ref()
+            .to_vec()
+    }
+
+    #[test]
+    fn mutations() {
+        let mut fs = MemFs::default();
+
+        fs.create_dir("/etc".as_ref()).unwrap();
+        assert_eq!(
+            fs.create_dir("/etc".as_ref()).unwrap_err(),
+            Error::AlreadyExists {
+                path: "/etc".into(),
+                source: None
+            }
+        );
+        fs.write_file("/etc/app.toml".as_ref(), b"a = 1\n").unwrap();
+        fs.append_file("/etc/app.toml".as_ref(), b"b = 2\n")
+            .unwrap();
+        fs.append_file("/etc/new.toml".as_ref(), b"c = 3\n")
+            .unwrap();
+        assert_eq!(read(&fs, "/etc/app.toml"), b"a = 1\nb = 2\n");
+        assert_eq!(read(&fs, "/etc/new.toml"), b"c = 3\n");
+
+        assert_eq!(
+            fs.write_file("/etc".as_ref(), b"").unwrap_err(),
+            Error::IsADirectory {
+                path: "/etc".into(),
+                source: None
+            }
+        );
+        assert_eq!(
+            fs.remove_file("/etc".as_ref()).unwrap_err(),
+            Error::IsADirectory {
+                path: "/etc".into(),
+                source: None
+            }
+        );
+        assert_eq!(
+            fs.remove_dir("/etc".as_ref()).unwrap_err(),
+            Error::DirectoryNotEmpty {
+                path: "/etc".into(),
+                source: None
+            }
+        );
+
+        fs.remove_file("/etc/new.toml".as_ref()).unwrap();
+        assert_eq!(
+            fs.remove_file("/etc/new.toml".as_ref()).unwrap_err(),
+            Error::not_found("/etc/new.toml")
+        );
+
+        fs.create_dir_all("/a/b/c".as_ref()).unwrap();
+        fs.remove_dir("/a/b/c".as_ref()).unwrap();
+        fs.remove_dir_all("/a".as_ref()).unwrap();
+        assert_eq!(
+            fs.read_dir("/".as_ref())
+                .unwrap()
+                .map(|e| e.unwrap().path)
+                .collect::<Vec<_>>(),
+            [PathBuf::from("/etc")]
+        );
+    }
+
+    #[test]
+    fn empty_dirs() {
+        let mut fs = MemFs::default();
+        assert_eq!(fs.read_dir("/".as_ref()).unwrap().count(), 0);
+        assert!(fs.metadata("/".as_ref()).unwrap().is_dir());
+
+        fs.create_dir_all("/a/b/c".as_ref()).unwrap();
+        fs.create_dir_all("/a/b".as_ref()).unwrap();
+        fs.create_dir("/a/empty".as_ref()).unwrap();
+        fs.write_file("/a/file".as_ref(), b"").unwrap();
+        assert_eq!(fs.read_dir("/a/empty".as_ref()).unwrap().count(), 0);
+        assert_eq!(
+            fs.read_dir("/a".as_ref())
+                .unwrap()
+                .map(|e| e.unwrap().path)
+                .collect::<Vec<_>>(),
+            [
+                PathBuf::from("/a/b"),
+                PathBuf::from("/a/empty"),
+                PathBuf::from("/a/file")
+            ]
+        );
+
+        let before = fs.clone();
+        assert_eq!(
+            fs.create_dir("/x/y".as_ref()).unwrap_err(),
+            Error::not_found("/x/y")
+        );
+        assert_eq!(
+            fs.create_dir_all("/a/file/x/y".as_ref()).unwrap_err(),
+            Error::NotADirectory {
+                path: "/a/file".into(),
+                source: None
+            }
+        );
+        assert_eq!(
+            fs.write_file("/x/file".as_ref(), b"").unwrap_err(),
+            Error::not_found("/x/file")
+        );
+        assert_eq!(
+            fs.append_file("/a/file/x".as_ref(), b"").unwrap_err(),
+            Error::NotADirectory {
+                path: "/a/file".into(),
+                source: None
+            }
+        );
+        assert_eq!(
+            fs.rename("/a/b".as_ref(), "/x/b".as_ref()).unwrap_err(),
+            Error::not_found("/x/b")
+        );
+        assert_eq!(fs, before);
+    }
 
     #[test]
-    fn basics() {
+    fn rename() {
         let mut fs = MemFs::default();
-        let hello_rs = b"fn hello() -> i32 {{\n42}}\n".as_slice();
-        let world_rs = b"fn world() -> i32 {{\n1337}}\n".as_slice();
+        fs.create_dir_all("/a/sub".as_ref()).unwrap();
+        fs.create_dir_all("/empty".as_ref()).unwrap();
+        fs.write_file("/a/sub/file".as_ref(), b"data").unwrap();
+        fs.write_file("/other".as_ref(), b"other").unwrap();
+
+        fs.rename("/other".as_ref(), "/a/sub/file".as_ref())
+            .unwrap();
+        assert_eq!(read(&fs, "/a/sub/file"), b"other");
 
         assert_eq!(
-            fs.set_static_file("/src/hello.rs", hello_rs, false)
+            fs.rename("/a".as_ref(), "/a/sub/moved".as_ref())
+                .unwrap_err(),
+            Error::InvalidPath {
+                path: "/a/sub/moved".into(),
+                source: None
+            }
+        );
+        assert_eq!(
+            fs.rename("/a/sub/file".as_ref(), "/empty".as_ref())
+                .unwrap_err(),
+            Error::IsADirectory {
+                path: "/empty".into(),
+                source: None
+            }
+        );
+        assert_eq!(
+            fs.rename("/empty".as_ref(), "/a/sub/file".as_ref())
                 .unwrap_err(),
-            Error::NotFound {
-                path: "/src/hello.rs".into()
+            Error::NotADirectory {
+                path: "/a/sub/file".into(),
+                source: None
+            }
+        );
+        assert_eq!(
+            fs.rename("/empty".as_ref(), "/a".as_ref()).unwrap_err(),
+            Error::DirectoryNotEmpty {
+                path: "/a".into(),
+                source: None
             }
         );
 
-        fs.set_static_file("/src/hello.rs", hello_rs, true).unwrap();
-        fs.set_static_file("/src/world.rs", world_rs, true).unwrap();
+        fs.rename("/a/sub/file".as_ref(), "/a/sub/file".as_ref())
+            .unwrap();
+        fs.create_dir("/other-empty".as_ref()).unwrap();
+        fs.rename("/other-empty".as_ref(), "/empty".as_ref())
+            .unwrap();
+        fs.rename("/a".as_ref(), "/empty".as_ref()).unwrap();
+        assert_eq!(read(&fs, "/empty/sub/file"), b"other");
+        assert_eq!(
+            fs.open_file("/a/sub/file".as_ref()).unwrap_err(),
+            Error::not_found("/a/sub/file")
+        );
+    }
+
+    #[test]
+    fn copy() {
+        let mut fs = MemFs::default();
+        fs.create_dir_all("/a/sub".as_ref()).unwrap();
+        fs.write_static_file("/a/static", b"static").unwrap();
+        fs.write_file("/a/file".as_ref(), b"data").unwrap();
+
+        assert_eq!(fs.copy("/a/file".as_ref(), "/a/copy".as_ref()), Ok(4));
+        fs.append_file("/a/copy".as_ref(), b" and more").unwrap();
+        assert_eq!(read(&fs, "/a/file"), b"data");
+        assert_eq!(read(&fs, "/a/copy"), b"data and more");
+
+        assert_eq!(fs.copy("/a/static".as_ref(), "/a/copy".as_ref()), Ok(6));
+        assert_eq!(read(&fs, "/a/copy"), b"static");
 
         assert_eq!(
-            fs.set_static_file("/src/hello.rs/world.rs", hello_rs, false)
+            fs.copy("/a/sub".as_ref(), "/a/dir-copy".as_ref())
+                .unwrap_err(),
+            Error::NotAFile {
+                path: "/a/sub".into()
+            }
+        );
+        assert_eq!(
+            fs.copy("/a/file".as_ref(), "/a/sub".as_ref()).unwrap_err(),
+            Error::IsADirectory {
+                path: "/a/sub".into(),
+                source: None
+            }
+        );
+        assert_eq!(
+            fs.copy("/a/missing".as_ref(), "/a/new".as_ref())
                 .unwrap_err(),
-            Error::Other(
-                "Cannot create directory \"/src/hello.rs\" as it is a file for \"/src/hello.rs/world.rs\"".into()
-            )
+            Error::not_found("/a/missing")
+        );
+        assert_eq!(
+            fs.copy("/a/file".as_ref(), "/b/file".as_ref()).unwrap_err(),
+            Error::not_found("/b/file")
         );
+    }
+
+    #[test]
+    fn writer() {
+        use std::io::Read;
+
+        let mut fs = MemFs::default();
+        fs.create_dir("/logs".as_ref()).unwrap();
+        fs.write_file("/logs/app.log".as_ref(), b"first\n").unwrap();
+        let mut old = fs.open_file("/logs/app.log".as_ref()).unwrap();
+
+        let mut file = fs.create_file("/logs/app.log").unwrap();
+        file.write_all(b"xxxxx second\n").unwrap();
+        file.rewind().unwrap();
+        file.write_all(b"first").unwrap();
+        file.commit().unwrap();
+        assert_eq!(read(&fs, "/logs/app.log"), b"first second\n");
+
+        let mut file = fs
+            .create_file_with("/logs/app.log", WriteMode::Append)
+            .unwrap();
+        file.rewind().unwrap();
+        writeln!(file, "third").unwrap();
+        drop(file);
+        assert_eq!(read(&fs, "/logs/app.log"), b"first second\nthird\n");
+
+        let mut data = String::new();
+        old.read_to_string(&mut data).unwrap();
+        assert_eq!(data, "first\n");
+
+        let mut file = fs.create_file("/logs/sparse").unwrap();
+        file.seek(SeekFrom::Start(2)).unwrap();
+        file.write_all(b"x").unwrap();
+        drop(file);
+        assert_eq!(read(&fs, "/logs/sparse"), b"\0\0x");
+
+        fs.create_file_with("/logs/new.log", WriteMode::Append)
+            .unwrap();
+        assert_eq!(read(&fs, "/logs/new.log"), b"");
 
         assert_eq!(
-            fs.open_file("/src/hello.rs".as_ref()).unwrap().get_ref().0,
-            FileData::Static(hello_rs)
+            fs.create_file("/logs").unwrap_err(),
+            Error::IsADirectory {
+                path: "/logs".into(),
+                source: None
+            }
+        );
+        assert_eq!(
+            fs.create_file("/missing/file").unwrap_err(),
+            Error::not_found("/missing/file")
+        );
+    }
+
+    #[test]
+    fn from_vfs() {
+        let mut src = MemFs::default();
+        src.create_dir_all("/app/conf.d".as_ref()).unwrap();
+        src.create_dir_all("/app/empty".as_ref()).unwrap();
+        src.write_file("/app/main.toml".as_ref(), b"a = 1\n")
+            .unwrap();
+        src.write_file("/app/conf.d/extra.toml".as_ref(), b"b = 2\n")
+            .unwrap();
+        src.write_file("/app/notes.txt".as_ref(), b"notes").unwrap();
+
+        let (fs, report) = MemFs::from_vfs(&src, "/app").unwrap();
+        let mut expected = MemFs::default();
+        expected.create_dir_all("/conf.d".as_ref()).unwrap();
+        expected.create_dir("/empty".as_ref()).unwrap();
+        expected
+            .write_file("/main.toml".as_ref(), b"a = 1\n")
+            .unwrap();
+        expected
+            .write_file("/conf.d/extra.toml".as_ref(), b"b = 2\n")
+            .unwrap();
+        expected
+            .write_file("/notes.txt".as_ref(), b"notes")
+            .unwrap();
+        assert_eq!(fs, expected);
+        assert_eq!(report.total_size(), 17);
+        assert!(report.skipped().is_empty());
+
+        let options = LoadOptions::new().filter(Pattern::new("**/*.toml").unwrap());
+        let (fs, report) = MemFs::from_vfs_with(&src, "/app", &options).unwrap();
+        expected.remove_file("/notes.txt".as_ref()).unwrap();
+        expected.remove_dir("/empty".as_ref()).unwrap();
+        assert_eq!(fs, expected);
+        assert_eq!(report.total_size(), 12);
+
+        assert_eq!(
+            MemFs::from_vfs_with(&src, "/app", &LoadOptions::new().max_size(16)).unwrap_err(),
+            Error::SizeLimitExceeded {
+                path: "/app/notes.txt".into(),
+                limit: 16,
+            }
+        );
+        assert_eq!(
+            MemFs::from_vfs(&src, "/app/main.toml").unwrap_err(),
+            Error::NotADirectory {
+                path: "/app/main.toml".into(),
+                source: None
+            }
         );
+    }
 
+    #[cfg(unix)]
+    #[test]
+    fn load_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("conf/empty")).unwrap();
+        std::fs::write(tmp.path().join("conf/app.toml"), b"a = 1\n").unwrap();
+        std::os::unix::fs::symlink("app.toml", tmp.path().join("conf/link.toml")).unwrap();
+        std::os::unix::fs::symlink("/etc/hosts", tmp.path().join("conf/hosts")).unwrap();
+
+        let (fs, report) = MemFs::load_dir(tmp.path()).unwrap();
+        assert_eq!(read(&fs, "/conf/app.toml"), b"a = 1\n");
+        assert_eq!(fs.read_dir("/conf/empty".as_ref()).unwrap().count(), 0);
         assert_eq!(
-            fs.open_file("/src".as_ref()).unwrap_err(),
-            Error::Other("The path \"/src\" is not a file".to_owned())
+            fs.read_link("/conf/link.toml".as_ref()).unwrap(),
+            Path::new("app.toml")
+        );
+        assert_eq!(read(&fs, "/conf/link.toml"), b"a = 1\n");
+        assert!(!fs.exists("/conf/hosts".as_ref()));
+        assert_eq!(
+            report
+                .skipped()
+                .iter()
+                .map(|e| (e.path(), e.file_type()))
+                .collect::<Vec<_>>(),
+            [(Path::new("/conf/hosts"), FileType::Symlink)]
         );
+    }
+
+    #[test]
+    fn read_helpers() {
+        let mut fs = MemFs::default();
+        fs.write_file("/config.toml".as_ref(), "név = 1\n".as_bytes())
+            .unwrap();
+        fs.write_file("/broken.toml".as_ref(), b"key = \"\xff\"\n")
+            .unwrap();
 
         assert_eq!(
-            fs.open_file("/src/hello.rs/world.rs".as_ref()).unwrap_err(),
+            fs.read("/config.toml".as_ref()).unwrap(),
+            "név = 1\n".as_bytes()
+        );
+        assert_eq!(
+            fs.read_to_string("/config.toml".as_ref()).unwrap(),
+            "név = 1\n"
+        );
+        assert_eq!(
+            fs.read_to_string("/broken.toml".as_ref()).unwrap_err(),
+            Error::InvalidUtf8 {
+                path: "/broken.toml".into(),
+                offset: 7,
+            }
+        );
+        assert_eq!(
+            fs.read("/missing.toml".as_ref()).unwrap_err(),
+            Error::not_found("/missing.toml")
+        );
+
+        assert_eq!(
+            fs.read("/confg.toml".as_ref())
+                .unwrap_err()
+                .with_suggestions(&fs)
+                .to_string(),
+            r#"Failed to locate "/confg.toml", did you mean "/config.toml"?"#
+        );
+
+        assert!(fs.exists("/config.toml".as_ref()));
+        assert!(fs.exists("/".as_ref()));
+        assert!(!fs.exists("/missing.toml".as_ref()));
+        assert_eq!(fs.try_exists("/missing.toml".as_ref()), Ok(false));
+        assert_eq!(
+            fs.try_exists("/config.toml/nested".as_ref()),
+            Err(Error::NotADirectory {
+                path: "/config.toml".into(),
+                source: None
+            })
+        );
+    }
+
+    #[test]
+    fn symlinks() {
+        let mut fs = MemFs::default();
+        fs.create_dir_all("/etc/conf.d".as_ref()).unwrap();
+        fs.write_file("/etc/conf.d/app.toml".as_ref(), b"a = 1")
+            .unwrap();
+        fs.symlink("conf.d/app.toml", "/etc/app.toml").unwrap();
+        fs.symlink("/etc/conf.d", "/conf").unwrap();
+        fs.symlink("../missing", "/etc/dangling").unwrap();
+        fs.symlink("../..", "/etc/escape").unwrap();
+        fs.symlink("loop-b", "/loop-a").unwrap();
+        fs.symlink("loop-a", "/loop-b").unwrap();
+
+        assert_eq!(read(&fs, "/etc/app.toml"), b"a = 1");
+        assert_eq!(read(&fs, "/conf/app.toml"), b"a = 1");
+        assert_eq!(
+            fs.canonicalize_path("/conf/../app.toml".as_ref()),
+            Ok("/etc/conf.d/app.toml".into())
+        );
+        assert_eq!(
+            fs.canonicalize_path("/conf/new.toml".as_ref()),
+            Ok("/etc/conf.d/new.toml".into())
+        );
+        assert_eq!(
+            fs.read_dir("/conf".as_ref())
+                .unwrap()
+                .map(|e| e.unwrap().path().to_path_buf())
+                .collect::<Vec<_>>(),
+            [PathBuf::from("/conf/app.toml")]
+        );
+        assert_eq!(read(&fs, "/nope/../conf/app.toml"), b"a = 1");
+        assert_eq!(
+            fs.read_dir("/etc/app.toml".as_ref()).unwrap_err(),
+            Error::NotADirectory {
+                path: "/etc/app.toml".into(),
+                source: None
+            }
+        );
+        assert!(fs
+            .read_dir("/etc".as_ref())
+            .unwrap()
+            .any(|e| e.unwrap().is_symlink()));
+        assert_eq!(
+            fs.metadata("/etc/app.toml".as_ref()).unwrap().file_type(),
+            FileType::File
+        );
+
+        assert_eq!(
+            fs.read_link("/etc/app.toml".as_ref()),
+            Ok("conf.d/app.toml".into())
+        );
+        assert_eq!(
+            fs.read_link("/etc/conf.d".as_ref()),
+            Err(Error::InvalidPath {
+                path: "/etc/conf.d".into(),
+                source: None
+            })
+        );
+        assert_eq!(
+            fs.open_file("/etc/dangling".as_ref()).unwrap_err(),
+            Error::not_found("/missing")
+        );
+        assert_eq!(
+            fs.open_file("/etc/escape".as_ref()).unwrap_err(),
+            Error::PathOutsideBounds {
+                path: "/etc/escape".into()
+            }
+        );
+        assert_eq!(
+            fs.open_file("/loop-a".as_ref()).unwrap_err(),
+            Error::TooManySymlinks {
+                path: "/loop-a".into(),
+                source: None
+            }
+        );
+        assert_eq!(
+            fs.symlink("anything", "/conf").unwrap_err(),
+            Error::AlreadyExists {
+                path: "/conf".into(),
+                source: None
+            }
+        );
+
+        // Writing goes through the link, removing and renaming affects the link itself
+        fs.write_file("/etc/dangling".as_ref(), b"b = 2").unwrap();
+        assert_eq!(read(&fs, "/missing"), b"b = 2");
+        assert_eq!(
+            fs.remove_dir("/conf".as_ref()).unwrap_err(),
             Error::NotADirectory {
-                path: "/src/hello.rs".into()
+                path: "/conf".into(),
+                source: None
             }
         );
+        fs.rename("/conf".as_ref(), "/config".as_ref()).unwrap();
+        assert_eq!(read(&fs, "/config/app.toml"), b"a = 1");
+        fs.remove_dir_all("/config".as_ref()).unwrap();
+        fs.remove_file("/etc/app.toml".as_ref()).unwrap();
+        assert!(!fs.exists("/config".as_ref()));
+        assert!(!fs.exists("/etc/app.toml".as_ref()));
+        assert_eq!(read(&fs, "/etc/conf.d/app.toml"), b"a = 1");
+    }
+
+    #[test]
+    fn hard_links() {
+        let mut fs = MemFs::default();
+        fs.create_dir_all("/dotfiles".as_ref()).unwrap();
+        fs.write_file("/dotfiles/bashrc".as_ref(), b"a").unwrap();
+        fs.hard_link("/dotfiles/bashrc", "/.bashrc").unwrap();
+        let nlink = |fs: &MemFs, path: &str| fs.metadata(path.as_ref()).unwrap().nlink();
+        assert_eq!(nlink(&fs, "/.bashrc"), Some(2));
+        assert_eq!(nlink(&fs, "/"), Some(3));
 
+        fs.append_file("/.bashrc".as_ref(), b"b").unwrap();
+        assert_eq!(read(&fs, "/dotfiles/bashrc"), b"ab");
         {
-            let mut it = fs.read_dir("/".as_ref()).unwrap();
-            let entry = it.next().unwrap().unwrap();
-            assert!(it.next().is_none());
+            let mut writer = fs.create_file("/dotfiles/bashrc").unwrap();
+            writer.write_all(b"c").unwrap();
+        }
+        assert_eq!(read(&fs, "/.bashrc"), b"c");
 
-            assert_eq!(entry.path(), Path::new("/src"),);
-            assert!(entry.is_dir());
-            assert!(!entry.is_file());
+        assert_eq!(
+            fs.hard_link("/dotfiles", "/dots").unwrap_err(),
+            Error::NotAFile {
+                path: "/dotfiles".into()
+            }
+        );
+        assert_eq!(
+            fs.hard_link("/.bashrc", "/dotfiles/bashrc").unwrap_err(),
+            Error::AlreadyExists {
+                path: "/dotfiles/bashrc".into(),
+                source: None
+            }
+        );
+
+        // Renaming onto another link of the same file keeps both
+        fs.rename("/.bashrc".as_ref(), "/dotfiles/bashrc".as_ref())
+            .unwrap();
+        assert_eq!(nlink(&fs, "/.bashrc"), Some(2));
+
+        fs.copy("/.bashrc".as_ref(), "/copy".as_ref()).unwrap();
+        assert_eq!(nlink(&fs, "/copy"), Some(1));
+        fs.remove_dir_all("/dotfiles".as_ref()).unwrap();
+        assert_eq!(nlink(&fs, "/.bashrc"), Some(1));
+        assert_eq!(read(&fs, "/.bashrc"), b"c");
+        fs.rename("/copy".as_ref(), "/.bashrc".as_ref()).unwrap();
+        fs.remove_file("/.bashrc".as_ref()).unwrap();
+        assert!(fs.inodes.is_empty());
+    }
+
+    #[test]
+    fn permissions() {
+        let mut fs = MemFs::default();
+        fs.create_dir_all("/home/alice/private".as_ref()).unwrap();
+        fs.write_file("/home/alice/private/key".as_ref(), b"secret")
+            .unwrap();
+        fs.write_file("/home/alice/notes.txt".as_ref(), b"notes")
+            .unwrap();
+        fs.set_owner("/home/alice", 1000, 1000).unwrap();
+        fs.set_owner("/home/alice/private", 1000, 1000).unwrap();
+        fs.set_owner("/home/alice/notes.txt", 1000, 1000).unwrap();
+        fs.set_permissions("/home/alice/private", 0o700).unwrap();
+        fs.set_permissions("/home/alice/notes.txt", 0o640).unwrap();
+
+        let meta = fs.metadata("/home/alice/notes.txt".as_ref()).unwrap();
+        assert_eq!(meta.mode(), Some(0o100640));
+        assert_eq!((meta.uid(), meta.gid()), (Some(1000), Some(1000)));
+
+        let denied = |path: &str| Error::AccessDenied {
+            path: path.into(),
+            source: None,
+        };
+        fs.set_user(User::new(1001, 1001));
+        assert_eq!(
+            fs.read("/home/alice/notes.txt".as_ref()).unwrap_err(),
+            denied("/home/alice/notes.txt")
+        );
+        assert_eq!(
+            fs.read_dir("/home/alice/private".as_ref()).unwrap_err(),
+            denied("/home/alice/private")
+        );
+        // Without search permission even missing entries and metadata are denied
+        assert_eq!(
+            fs.metadata("/home/alice/private/key".as_ref()).unwrap_err(),
+            denied("/home/alice/private/key")
+        );
+        assert_eq!(
+            fs.metadata("/home/alice/private/missing".as_ref())
+                .unwrap_err(),
+            denied("/home/alice/private/missing")
+        );
+        for path in [
+            "/home/alice/nope/../private/key",
+            "/nope/../home/alice/private/key",
+        ] {
+            assert_eq!(fs.read_to_string(path.as_ref()).unwrap_err(), denied(path));
         }
+        assert_eq!(
+            fs.write_file("/home/alice/new.txt".as_ref(), b"")
+                .unwrap_err(),
+            denied("/home/alice/new.txt")
+        );
+        assert_eq!(
+            fs.remove_dir_all("/home/alice".as_ref()).unwrap_err(),
+            denied("/home/alice")
+        );
+        assert_eq!(
+            fs.set_permissions("/home/alice/notes.txt", 0o666)
+                .unwrap_err(),
+            denied("/home/alice/notes.txt")
+        );
 
-        {
-            let mut it = fs.read_dir("/src".as_ref()).unwrap();
-            let file1 = it.next().unwrap().unwrap();
-            let file2 = it.next().unwrap().unwrap();
-            assert!(it.next().is_none());
+        fs.set_user(User::new(1001, 1001).with_groups([1000]));
+        assert_eq!(read(&fs, "/home/alice/notes.txt"), b"notes");
+        assert_eq!(
+            fs.write_file("/home/alice/notes.txt".as_ref(), b"")
+                .unwrap_err(),
+            denied("/home/alice/notes.txt")
+        );
 
-            assert_eq!(file1.path(), Path::new("/src/hello.rs"),);
-            assert_eq!(file2.path(), Path::new("/src/world.rs"),);
+        // Read permission on a directory without search permission only lists the names
+        fs.set_user(User::root());
+        fs.set_permissions("/home/alice/private", 0o744).unwrap();
+        fs.set_user(User::new(1001, 1001));
+        assert_eq!(
+            fs.read_dir("/home/alice/private".as_ref()).unwrap().count(),
+            1
+        );
+        assert_eq!(
+            fs.read("/home/alice/private/key".as_ref()).unwrap_err(),
+            denied("/home/alice/private/key")
+        );
 
-            assert!(!file1.is_dir());
-            assert!(!file2.is_dir());
+        fs.set_user(User::new(1000, 1000));
+        assert_eq!(read(&fs, "/home/alice/private/key"), b"secret");
+        fs.write_file("/home/alice/new.txt".as_ref(), b"").unwrap();
+        assert_eq!(
+            fs.metadata("/home/alice/new.txt".as_ref()).unwrap().mode(),
+            Some(0o100644)
+        );
+        assert_eq!(
+            fs.set_owner("/home/alice/new.txt", 0, 1000).unwrap_err(),
+            denied("/home/alice/new.txt")
+        );
+        assert_eq!(
+            fs.create_dir("/home/bob".as_ref()).unwrap_err(),
+            denied("/home/bob")
+        );
+    }
 
-            assert!(file1.is_file());
-            assert!(file2.is_file());
+    #[test]
+    fn timestamps() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let clock = ManualClock::new(start);
+        let mut fs = MemFs::with_clock(clock.clone());
+        let times = |fs: &MemFs, path: &str| {
+            let meta = fs.metadata(path.as_ref()).unwrap();
+            [
+                meta.created(),
+                meta.modified(),
+                meta.accessed(),
+                meta.changed(),
+            ]
+            .map(|t| t.unwrap().duration_since(start).unwrap().as_secs())
+        };
+
+        fs.create_dir("/etc".as_ref()).unwrap();
+        clock.advance(Duration::from_secs(10));
+        fs.write_file("/etc/app.toml".as_ref(), b"a = 1").unwrap();
+        assert_eq!(times(&fs, "/etc/app.toml"), [10, 10, 10, 10]);
+        assert_eq!(times(&fs, "/etc"), [0, 10, 10, 10]);
+        assert_eq!(times(&fs, "/"), [0, 0, 0, 0]);
+
+        clock.advance(Duration::from_secs(10));
+        fs.read("/etc/app.toml".as_ref()).unwrap();
+        fs.append_file("/etc/app.toml".as_ref(), b"\n").unwrap();
+        assert_eq!(times(&fs, "/etc/app.toml"), [10, 20, 20, 20]);
+        assert_eq!(times(&fs, "/etc"), [0, 10, 10, 10]);
+
+        // Changing the metadata only updates the change time
+        clock.advance(Duration::from_secs(10));
+        fs.set_permissions("/etc/app.toml", 0o600).unwrap();
+        assert_eq!(times(&fs, "/etc/app.toml"), [10, 20, 20, 30]);
+
+        clock.advance(Duration::from_secs(10));
+        fs.create_dir("/backup".as_ref()).unwrap();
+        fs.rename("/etc/app.toml".as_ref(), "/backup/app.toml".as_ref())
+            .unwrap();
+        assert_eq!(times(&fs, "/backup/app.toml"), [10, 20, 20, 40]);
+        assert_eq!(times(&fs, "/etc"), [0, 40, 40, 40]);
+        assert_eq!(times(&fs, "/"), [0, 40, 40, 40]);
+
+        clock.set(start + Duration::from_secs(100));
+        {
+            let mut writer = fs.create_file("/backup/app.toml").unwrap();
+            writer.write_all(b"a = 2\n").unwrap();
         }
+        assert_eq!(times(&fs, "/backup/app.toml"), [10, 100, 100, 100]);
+    }
+
+    #[test]
+    fn suggestions() {
+        let mut fs = MemFs::default();
+        fs.create_dir_all("/etc/conf.d".as_ref()).unwrap();
+        fs.write_file("/etc/App.toml".as_ref(), b"").unwrap();
+        fs.write_file("/etc/app.yaml".as_ref(), b"").unwrap();
+        fs.write_file("/etc/conf.d/a.toml".as_ref(), b"").unwrap();
+
+        let suggest = |path: &str| match fs
+            .metadata(path.as_ref())
+            .unwrap_err()
+            .with_suggestions(&fs)
+        {
+            Error::NotFound { suggestions, .. } => suggestions
+                .iter()
+                .map(|p| p.to_string_lossy().into_owned())
+                .collect::<Vec<_>>(),
+            e => panic!("Expected NotFound, got {e:?}"),
+        };
+        assert_eq!(suggest("/etc/app.toml"), ["/etc/App.toml", "/etc/app.yaml"]);
+        assert_eq!(suggest("/etc/app"), ["/etc/app.yaml", "/etc/App.toml"]);
+        assert_eq!(suggest("/etc/conf/a.toml"), ["/etc/conf.d/a.toml"]);
+        assert_eq!(suggest("/ect/app.yaml"), ["/etc/app.yaml"]);
+        assert_eq!(
+            fs.suggest_paths("etc/conf.d/b.toml".as_ref()),
+            [PathBuf::from("etc/conf.d/a.toml")]
+        );
+        assert!(suggest("/etc/unrelated").is_empty());
+
+        assert_eq!(
+            Error::AccessDenied {
+                path: "/a".into(),
+                source: None
+            }
+            .with_suggestions(&fs),
+            Error::AccessDenied {
+                path: "/a".into(),
+                source: None
+            }
+        );
     }
 }
@@ -0,0 +1,187 @@
+use std::time::SystemTime;
+
+use crate::LogixVfsDirEntry;
+
+/// The type of an entry in a [crate::LogixVfs]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum FileType {
+    File,
+    Dir,
+    Symlink,
+    /// Devices, sockets, pipes and other special files
+    Other,
+}
+
+impl FileType {
+    /// The type of a directory entry returned by any [crate::LogixVfs]
+    pub(crate) fn of(entry: &impl LogixVfsDirEntry) -> Self {
+        if entry.is_symlink() {
+            Self::Symlink
+        } else if entry.is_dir() {
+            Self::Dir
+        } else if entry.is_file() {
+            Self::File
+        } else {
+            Self::Other
+        }
+    }
+}
+
+impl From<std::fs::FileType> for FileType {
+    fn from(ty: std::fs::FileType) -> Self {
+        if ty.is_symlink() {
+            Self::Symlink
+        } else if ty.is_dir() {
+            Self::Dir
+        } else if ty.is_file() {
+            Self::File
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// Backend neutral metadata about a file or directory. The timestamps and
+/// the mode are only available if the backend knows about them.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Metadata {
+    pub(crate) file_type: FileType,
+    pub(crate) len: u64,
+    pub(crate) modified: Option<SystemTime>,
+    pub(crate) accessed: Option<SystemTime>,
+    pub(crate) created: Option<SystemTime>,
+    pub(crate) changed: Option<SystemTime>,
+    pub(crate) readonly: bool,
+    pub(crate) mode: Option<u32>,
+    pub(crate) nlink: Option<u64>,
+    pub(crate) uid: Option<u32>,
+    pub(crate) gid: Option<u32>,
+}
+
+impl Metadata {
+    /// Create metadata with only the type and length known, intended for
+    /// backends implemented outside this crate
+    pub fn new(file_type: FileType, len: u64) -> Self {
+        Self {
+            file_type,
+            len,
+            modified: None,
+            accessed: None,
+            created: None,
+            changed: None,
+            readonly: false,
+            mode: None,
+            nlink: None,
+            uid: None,
+            gid: None,
+        }
+    }
+
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.file_type == FileType::File
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.file_type == FileType::Dir
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.file_type == FileType::Symlink
+    }
+
+    /// The size of the file in bytes
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The last modification time (mtime)
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+
+    /// The last access time (atime)
+    pub fn accessed(&self) -> Option<SystemTime> {
+        self.accessed
+    }
+
+    /// The creation time, also known as birth time
+    pub fn created(&self) -> Option<SystemTime> {
+        self.created
+    }
+
+    /// The last status change time (ctime)
+    pub fn changed(&self) -> Option<SystemTime> {
+        self.changed
+    }
+
+    pub fn readonly(&self) -> bool {
+        self.readonly
+    }
+
+    /// The unix mode bits, including the file type bits
+    pub fn mode(&self) -> Option<u32> {
+        self.mode
+    }
+
+    /// The number of hard links to the file
+    pub fn nlink(&self) -> Option<u64> {
+        self.nlink
+    }
+
+    /// The user id of the owner
+    pub fn uid(&self) -> Option<u32> {
+        self.uid
+    }
+
+    /// The group id of the owner
+    pub fn gid(&self) -> Option<u32> {
+        self.gid
+    }
+}
+
+impl From<std::fs::Metadata> for Metadata {
+    fn from(meta: std::fs::Metadata) -> Self {
+        #[cfg(unix)]
+        let (changed, mode, nlink, uid, gid) = {
+            use std::os::unix::fs::MetadataExt;
+
+            let changed = u64::try_from(meta.ctime()).ok().and_then(|secs| {
+                SystemTime::UNIX_EPOCH.checked_add(std::time::Duration::new(
+                    secs,
+                    meta.ctime_nsec().try_into().unwrap_or(0),
+                ))
+            });
+            (
+                changed,
+                Some(meta.mode()),
+                Some(meta.nlink()),
+                Some(meta.uid()),
+                Some(meta.gid()),
+            )
+        };
+        #[cfg(not(unix))]
+        let (changed, mode, nlink, uid, gid) = (None, None, None, None, None);
+
+        Self {
+            file_type: meta.file_type().into(),
+            len: meta.len(),
+            modified: meta.modified().ok(),
+            accessed: meta.accessed().ok(),
+            created: meta.created().ok(),
+            changed,
+            readonly: meta.permissions().readonly(),
+            mode,
+            nlink,
+            uid,
+            gid,
+        }
+    }
+}
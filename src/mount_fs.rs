@@ -0,0 +1,442 @@
+use std::{
+    collections::BTreeMap,
+    ffi::OsString,
+    ops::Bound,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{Error, FileType, LogixVfs, LogixVfsDirEntry, Metadata};
+
+/// Composes several file systems by mounting them under path prefixes. Each
+/// operation is routed to the file system with the longest mount point
+/// matching the path, which receives the remainder of the path as an
+/// absolute path.
+///
+/// Parent directories of mount points that are not served by any file
+/// system show up as empty directories, and `..` is never allowed to leave
+/// a mount point, so one mount can't be used to reach a sibling.
+///
+/// All mounted file systems have the same type, use
+/// `Arc<dyn DynLogixVfs>` to mix different kinds of file systems.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct MountFs<V: LogixVfs> {
+    mounts: BTreeMap<PathBuf, V>,
+}
+
+impl<V: LogixVfs> Default for MountFs<V> {
+    fn default() -> Self {
+        Self {
+            mounts: BTreeMap::new(),
+        }
+    }
+}
+
+/// The result of routing a path to a mounted file system
+struct Route<'a, V> {
+    mount_point: &'a Path,
+    vfs: &'a V,
+    inner: PathBuf,
+}
+
+impl<V> Route<'_, V> {
+    /// Convert a path as seen by the mounted file system to a path in the
+    /// [MountFs], file systems such as [crate::RelFs] return paths that are
+    /// relative to their root
+    fn outer_path(&self, path: &Path) -> PathBuf {
+        self.mount_point
+            .join(path.strip_prefix("/").unwrap_or(path))
+    }
+
+    fn map_err(&self, e: Error) -> Error {
+        e.map_path(|path| self.outer_path(&path))
+    }
+}
+
+impl<V: LogixVfs> MountFs<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mount `vfs` at `mount_point`, which does not need to exist in any of
+    /// the other mounted file systems
+    pub fn mount(&mut self, mount_point: impl AsRef<Path>, vfs: V) -> Result<(), Error> {
+        let mount_point = self.resolve_path(mount_point.as_ref())?;
+        if self.mounts.contains_key(&mount_point) {
+            return Err(Error::AlreadyExists {
+                path: mount_point,
+                source: None,
+            });
+        }
+        self.mounts.insert(mount_point, vfs);
+        Ok(())
+    }
+
+    /// Remove the file system mounted at `mount_point`
+    pub fn unmount(&mut self, mount_point: impl AsRef<Path>) -> Result<V, Error> {
+        let path = mount_point.as_ref();
+        self.resolve_path(path)
+            .ok()
+            .and_then(|mount_point| self.mounts.remove(&mount_point))
+            .ok_or_else(|| Error::not_found(path))
+    }
+
+    /// Iterate the mount points and the file systems mounted there
+    pub fn mounts(&self) -> impl Iterator<Item = (&Path, &V)> {
+        self.mounts.iter().map(|(k, v)| (k.as_path(), v))
+    }
+
+    /// Lexically resolve `path` to an absolute path, refusing to use `..`
+    /// to leave a mount point
+    fn resolve_path(&self, path: &Path) -> Result<PathBuf, Error> {
+        let mut ret = PathBuf::from("/");
+
+        for cur in path.components() {
+            match cur {
+                Component::RootDir => ret = PathBuf::from("/"),
+                Component::CurDir => {}
+                Component::Normal(name) => ret.push(name),
+                Component::ParentDir => {
+                    if self.mounts.contains_key(&ret) || !ret.pop() {
+                        return Err(Error::PathOutsideBounds {
+                            path: path.to_path_buf(),
+                        });
+                    }
+                }
+                Component::Prefix(_) => {
+                    // NOTE(2026.10): Should never happen on platforms other than Windows
+                    return Err(Error::InvalidPath {
+                        path: path.to_path_buf(),
+                        source: None,
+                    });
+                }
+            }
+        }
+
+        Ok(ret)
+    }
+
+    fn route<'a>(&'a self, full_path: &'a Path) -> Option<Route<'a, V>> {
+        full_path.ancestors().find_map(|mount_point| {
+            let vfs = self.mounts.get(mount_point)?;
+            let rel = full_path.strip_prefix(mount_point).ok()?;
+            Some(Route {
+                mount_point,
+                vfs,
+                inner: Path::new("/").join(rel),
+            })
+        })
+    }
+
+    /// The names of the mount points directly below `full_path`
+    fn mounts_below<'a>(
+        &'a self,
+        full_path: &'a Path,
+    ) -> impl Iterator<Item = &'a std::ffi::OsStr> {
+        self.mounts
+            .range::<Path, _>((Bound::Included(full_path), Bound::Unbounded))
+            .map(|(k, _)| k)
+            .take_while(move |k| k.starts_with(full_path))
+            .filter_map(move |k| k.strip_prefix(full_path).ok()?.iter().next())
+    }
+
+    /// Returns true if there are mount points below `full_path`, making it
+    /// a directory even if no mounted file system has it
+    fn is_synthetic_dir(&self, full_path: &Path) -> bool {
+        self.mounts_below(full_path).next().is_some()
+    }
+
+    fn not_a_file(path: &Path) -> Error {
+        Error::NotAFile {
+            path: path.to_path_buf(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct DirEntry {
+    path: PathBuf,
+    ty: FileType,
+}
+
+impl LogixVfsDirEntry for DirEntry {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn is_dir(&self) -> bool {
+        self.ty == FileType::Dir
+    }
+
+    fn is_file(&self) -> bool {
+        self.ty == FileType::File
+    }
+
+    fn is_symlink(&self) -> bool {
+        self.ty == FileType::Symlink
+    }
+}
+
+pub type ReadDir = std::vec::IntoIter<Result<DirEntry, Error>>;
+
+impl<V: LogixVfs> LogixVfs for MountFs<V> {
+    type RoFile = V::RoFile;
+    type DirEntry = DirEntry;
+    type ReadDir = ReadDir;
+
+    fn canonicalize_path(&self, path: &Path) -> Result<PathBuf, Error> {
+        let full_path = self.resolve_path(path)?;
+        match self.route(&full_path) {
+            Some(route) => match route.vfs.canonicalize_path(&route.inner) {
+                Ok(path) => Ok(route.outer_path(&path)),
+                Err(e) => Err(route.map_err(e)),
+            },
+            None => Ok(full_path),
+        }
+    }
+
+    fn open_file(&self, path: &Path) -> Result<Self::RoFile, Error> {
+        let full_path = self.resolve_path(path)?;
+        match self.route(&full_path) {
+            Some(route) => match route.vfs.open_file(&route.inner) {
+                Err(Error::NotFound { .. }) if self.is_synthetic_dir(&full_path) => {
+                    Err(Self::not_a_file(path))
+                }
+                res => res.map_err(|e| route.map_err(e)),
+            },
+            None if self.is_synthetic_dir(&full_path) => Err(Self::not_a_file(path)),
+            None => Err(Error::not_found(path)),
+        }
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<Self::ReadDir, Error> {
+        let full_path = self.resolve_path(path)?;
+        let is_synthetic = self.is_synthetic_dir(&full_path);
+        let mut entries = BTreeMap::<OsString, _>::new();
+        let mut errors = Vec::new();
+
+        if let Some(route) = self.route(&full_path) {
+            match route.vfs.read_dir(&route.inner) {
+                Ok(it) => {
+                    for entry in it {
+                        match entry {
+                            Ok(entry) => {
+                                if let Some(name) = entry.path().file_name() {
+                                    let entry = DirEntry {
+                                        path: full_path.join(name),
+                                        ty: FileType::of(&entry),
+                                    };
+                                    entries.insert(name.to_owned(), entry);
+                                }
+                            }
+                            Err(e) => errors.push(Err(route.map_err(e))),
+                        }
+                    }
+                }
+                Err(Error::NotFound { .. }) if is_synthetic => {}
+                Err(e) => return Err(route.map_err(e)),
+            }
+        } else if !is_synthetic {
+            return Err(Error::not_found(path));
+        }
+
+        for name in self.mounts_below(&full_path) {
+            entries.insert(
+                name.to_owned(),
+                DirEntry {
+                    path: full_path.join(name),
+                    ty: FileType::Dir,
+                },
+            );
+        }
+
+        Ok(entries
+            .into_values()
+            .map(Ok)
+            .chain(errors)
+            .collect::<Vec<_>>()
+            .into_iter())
+    }
+
+    fn metadata(&self, path: &Path) -> Result<Metadata, Error> {
+        let full_path = self.resolve_path(path)?;
+        match self.route(&full_path) {
+            Some(route) => match route.vfs.metadata(&route.inner) {
+                Err(Error::NotFound { .. }) if self.is_synthetic_dir(&full_path) => {
+                    Ok(Metadata::new(FileType::Dir, 0))
+                }
+                res => res.map_err(|e| route.map_err(e)),
+            },
+            None if self.is_synthetic_dir(&full_path) => Ok(Metadata::new(FileType::Dir, 0)),
+            None => Err(Error::not_found(path)),
+        }
+    }
+
+    fn read_link(&self, path: &Path) -> Result<PathBuf, Error> {
+        let full_path = self.resolve_path(path)?;
+        match self.route(&full_path) {
+            Some(route) => match route.vfs.read_link(&route.inner) {
+                Err(Error::NotFound { .. }) if self.is_synthetic_dir(&full_path) => {
+                    Err(Error::InvalidPath {
+                        path: path.to_path_buf(),
+                        source: None,
+                    })
+                }
+                res => res.map_err(|e| route.map_err(e)),
+            },
+            None if self.is_synthetic_dir(&full_path) => Err(Error::InvalidPath {
+                path: path.to_path_buf(),
+                source: None,
+            }),
+            None => Err(Error::not_found(path)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemFs;
+
+    fn list(fs: &MountFs<MemFs>, path: &str) -> Vec<(PathBuf, bool)> {
+        fs.read_dir(path.as_ref())
+            .unwrap()
+            .map(|e| {
+                let e = e.unwrap();
+                (e.path().to_path_buf(), e.is_dir())
+            })
+            .collect()
+    }
+
+    fn test_fs() -> MountFs<MemFs> {
+        let mut fs = MountFs::new();
+        fs.mount(
+            "/builtin",
+            MemFs::with_files(&[("/defaults.toml", "/defaults.toml")]),
+        )
+        .unwrap();
+        fs.mount(
+            "/home",
+            MemFs::with_files(&[
+                ("/.bashrc", "/.bashrc"),
+                ("/.config/app.toml", "/.config/app.toml"),
+            ]),
+        )
+        .unwrap();
+        fs.mount(
+            "/home/.config/app",
+            MemFs::with_files(&[("/mounted.toml", "/mounted.toml")]),
+        )
+        .unwrap();
+        fs.mount(
+            "/deep/nested/project",
+            MemFs::with_files(&[("/Cargo.toml", "/Cargo.toml")]),
+        )
+        .unwrap();
+        fs
+    }
+
+    #[test]
+    fn routing() {
+        let fs = test_fs();
+
+        assert_eq!(
+            fs.read_to_string("/builtin/defaults.toml".as_ref())
+                .unwrap(),
+            "/defaults.toml"
+        );
+        assert_eq!(
+            fs.read_to_string("home/.bashrc".as_ref()).unwrap(),
+            "/.bashrc"
+        );
+        assert_eq!(
+            fs.read_to_string("/home/.config/app/mounted.toml".as_ref())
+                .unwrap(),
+            "/mounted.toml"
+        );
+        assert_eq!(
+            fs.read_to_string("/deep/nested/project/Cargo.toml".as_ref())
+                .unwrap(),
+            "/Cargo.toml"
+        );
+        assert_eq!(
+            fs.read_to_string("/home/missing.toml".as_ref())
+                .unwrap_err(),
+            Error::not_found("/home/missing.toml")
+        );
+        assert_eq!(
+            fs.read_to_string("/home/.bashrc/nope".as_ref())
+                .unwrap_err(),
+            Error::NotADirectory {
+                path: "/home/.bashrc".into(),
+                source: None
+            }
+        );
+        assert_eq!(
+            fs.read_to_string("/deep".as_ref()).unwrap_err(),
+            Error::NotAFile {
+                path: "/deep".into()
+            }
+        );
+
+        assert_eq!(
+            fs.canonicalize_path("/home/./.config/../.bashrc".as_ref()),
+            Ok("/home/.bashrc".into())
+        );
+        assert!(fs.metadata("/deep/nested".as_ref()).unwrap().is_dir());
+        assert!(fs.metadata("/home/.config/app".as_ref()).unwrap().is_dir());
+    }
+
+    #[test]
+    fn synthetic_dirs() {
+        let fs = test_fs();
+
+        assert_eq!(
+            list(&fs, "/"),
+            [
+                ("/builtin".into(), true),
+                ("/deep".into(), true),
+                ("/home".into(), true),
+            ]
+        );
+        assert_eq!(list(&fs, "/deep"), [("/deep/nested".into(), true)]);
+        assert_eq!(
+            list(&fs, "/home/.config"),
+            [
+                ("/home/.config/app".into(), true),
+                ("/home/.config/app.toml".into(), false),
+            ]
+        );
+        assert_eq!(
+            fs.read_dir("/nope".as_ref()).unwrap_err(),
+            Error::not_found("/nope")
+        );
+    }
+
+    #[test]
+    fn no_crossing_between_mounts() {
+        let fs = test_fs();
+
+        for path in [
+            "/home/../builtin/defaults.toml",
+            "/home/.config/app/../app.toml",
+            "/../builtin/defaults.toml",
+        ] {
+            assert_eq!(
+                fs.read_to_string(path.as_ref()).unwrap_err(),
+                Error::PathOutsideBounds { path: path.into() },
+                "{path:?}"
+            );
+        }
+
+        assert_eq!(
+            fs.read_to_string("/deep/nested/../nested/project/Cargo.toml".as_ref())
+                .unwrap(),
+            "/Cargo.toml"
+        );
+        assert_eq!(
+            fs.read_to_string("/home/.config/../.bashrc".as_ref())
+                .unwrap(),
+            "/.bashrc"
+        );
+    }
+}
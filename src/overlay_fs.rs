@@ -0,0 +1,355 @@
+use std::{
+    collections::BTreeMap,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use crate::{utils::PathUtil, Error, LogixVfs, LogixVfsDirEntry, Metadata};
+
+/// Stacks several file systems on top of each other. Layers added later are
+/// on top and hide files with the same path in the layers below, while
+/// directories are merged.
+///
+/// Paths are canonicalized by the overlay itself and passed to each layer as
+/// absolute paths, so all layers see the same path no matter which of them
+/// ends up serving it.
+///
+/// All layers share the same type, to stack different backends, for example
+/// built-in defaults in a [MemFs](crate::MemFs) below system and user
+/// directories on disk, use `OverlayFs<Arc<dyn DynLogixVfs>>`.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct OverlayFs<V: LogixVfs> {
+    layers: Vec<V>,
+}
+
+impl<V: LogixVfs> Default for OverlayFs<V> {
+    fn default() -> Self {
+        Self { layers: Vec::new() }
+    }
+}
+
+impl<V: LogixVfs> From<Vec<V>> for OverlayFs<V> {
+    /// The last layer in the list is the topmost one
+    fn from(layers: Vec<V>) -> Self {
+        Self { layers }
+    }
+}
+
+impl<V: LogixVfs> OverlayFs<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a layer on top of the existing ones, returning its index
+    pub fn push_layer(&mut self, layer: V) -> usize {
+        self.layers.push(layer);
+        self.layers.len() - 1
+    }
+
+    /// All layers, starting with the bottom one
+    pub fn layers(&self) -> &[V] {
+        &self.layers
+    }
+
+    pub fn layer(&self, index: usize) -> Option<&V> {
+        self.layers.get(index)
+    }
+
+    /// Returns the index of the layer that serves `path`, that is the
+    /// topmost layer where it exists
+    pub fn layer_of(&self, path: &Path) -> Result<usize, Error> {
+        self.find_topmost(path, |layer, path| layer.metadata(path))
+            .map(|(i, _)| i)
+    }
+
+    fn resolve_path(&self, path: &Path) -> Result<PathBuf, Error> {
+        PathUtil {
+            root: "/".as_ref(),
+            cur_dir: "/".as_ref(),
+        }
+        .resolve_path(false, path)
+    }
+
+    /// Calls `f` for each layer from the top until one of them returns
+    /// anything but [Error::NotFound]
+    fn find_topmost<T>(
+        &self,
+        path: &Path,
+        f: impl Fn(&V, &Path) -> Result<T, Error>,
+    ) -> Result<(usize, T), Error> {
+        let full_path = self.resolve_path(path)?;
+        for (i, layer) in self.layers.iter().enumerate().rev() {
+            match f(layer, &full_path) {
+                Ok(ret) => return Ok((i, ret)),
+                Err(Error::NotFound { .. }) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Err(Error::not_found(path))
+    }
+}
+
+/// An entry in a merged directory listing
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct DirEntry<E> {
+    path: PathBuf,
+    layer: usize,
+    inner: E,
+}
+
+impl<E> DirEntry<E> {
+    /// The index of the layer this entry comes from
+    pub fn layer(&self) -> usize {
+        self.layer
+    }
+
+    /// The entry as returned by the layer
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+}
+
+impl<E: LogixVfsDirEntry> LogixVfsDirEntry for DirEntry<E> {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn is_dir(&self) -> bool {
+        self.inner.is_dir()
+    }
+
+    fn is_file(&self) -> bool {
+        self.inner.is_file()
+    }
+
+    fn is_symlink(&self) -> bool {
+        self.inner.is_symlink()
+    }
+}
+
+pub type ReadDir<E> = std::vec::IntoIter<Result<DirEntry<E>, Error>>;
+
+impl<V: LogixVfs> LogixVfs for OverlayFs<V> {
+    type RoFile = V::RoFile;
+    type DirEntry = DirEntry<V::DirEntry>;
+    type ReadDir = ReadDir<V::DirEntry>;
+
+    fn canonicalize_path(&self, path: &Path) -> Result<PathBuf, Error> {
+        self.resolve_path(path)
+    }
+
+    fn open_file(&self, path: &Path) -> Result<Self::RoFile, Error> {
+        self.find_topmost(path, |layer, path| layer.open_file(path))
+            .map(|(_, file)| file)
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<Self::ReadDir, Error> {
+        let full_path = self.resolve_path(path)?;
+        let mut found = false;
+        let mut entries = BTreeMap::<OsString, _>::new();
+        let mut errors = Vec::new();
+
+        for (i, layer) in self.layers.iter().enumerate().rev() {
+            let it = match layer.read_dir(&full_path) {
+                Ok(it) => it,
+                Err(Error::NotFound { .. }) => continue,
+                // NOTE(2026.10): A directory in an upper layer hides a file with the same path below it
+                Err(Error::NotADirectory { .. }) if found => break,
+                Err(e) => return Err(e),
+            };
+            found = true;
+
+            for entry in it {
+                match entry {
+                    Ok(entry) => {
+                        let Some(name) = entry.path().file_name().map(|n| n.to_owned()) else {
+                            continue;
+                        };
+                        entries.entry(name.clone()).or_insert_with(|| DirEntry {
+                            path: full_path.join(name),
+                            layer: i,
+                            inner: entry,
+                        });
+                    }
+                    Err(e) => errors.push(Err(e)),
+                }
+            }
+        }
+
+        if !found {
+            return Err(Error::not_found(path));
+        }
+
+        Ok(entries
+            .into_values()
+            .map(Ok)
+            .chain(errors)
+            .collect::<Vec<_>>()
+            .into_iter())
+    }
+
+    fn metadata(&self, path: &Path) -> Result<Metadata, Error> {
+        self.find_topmost(path, |layer, path| layer.metadata(path))
+            .map(|(_, meta)| meta)
+    }
+
+    fn read_link(&self, path: &Path) -> Result<PathBuf, Error> {
+        self.find_topmost(path, |layer, path| layer.read_link(path))
+            .map(|(_, target)| target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{mem_fs::User, DynLogixVfs, MemFs, RelFs};
+
+    #[test]
+    fn basics() {
+        let mut fs = OverlayFs::new();
+        let defaults = fs.push_layer(MemFs::with_files(&[
+            ("/logix/config.toml", "defaults"),
+            ("/logix/defaults.toml", "defaults"),
+            ("/logix/shadowed/file", "defaults"),
+        ]));
+        let system = fs.push_layer(MemFs::with_files(&[
+            ("/logix/config.toml", "system"),
+            ("/logix/system.toml", "system"),
+        ]));
+        let user = fs.push_layer(MemFs::with_files(&[
+            ("/logix/user.toml", "user"),
+            ("/logix/shadowed", "user"),
+        ]));
+
+        assert_eq!(
+            fs.read_to_string("/logix/config.toml".as_ref()).unwrap(),
+            "system"
+        );
+        assert_eq!(
+            fs.read_to_string("logix/../logix/defaults.toml".as_ref())
+                .unwrap(),
+            "defaults"
+        );
+        assert_eq!(
+            fs.read_to_string("/logix/shadowed".as_ref()).unwrap(),
+            "user"
+        );
+        assert_eq!(
+            fs.open_file("/logix/shadowed/file".as_ref()).unwrap_err(),
+            Error::NotADirectory {
+                path: "/logix/shadowed".into(),
+                source: None
+            }
+        );
+        assert_eq!(
+            fs.open_file("/logix/missing.toml".as_ref()).unwrap_err(),
+            Error::not_found("/logix/missing.toml")
+        );
+
+        assert_eq!(fs.layer_of("/logix/config.toml".as_ref()), Ok(system));
+        assert_eq!(fs.layer_of("/logix/defaults.toml".as_ref()), Ok(defaults));
+        assert_eq!(fs.layer_of("/logix/user.toml".as_ref()), Ok(user));
+        assert_eq!(fs.layer_of("/logix".as_ref()), Ok(user));
+
+        assert_eq!(
+            fs.canonicalize_path("logix/./user.toml".as_ref()),
+            Ok("/logix/user.toml".into())
+        );
+
+        let entries: Vec<_> = fs
+            .read_dir("/logix".as_ref())
+            .unwrap()
+            .map(|e| {
+                let e = e.unwrap();
+                (e.path().to_path_buf(), e.layer(), e.is_file())
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("/logix/config.toml".into(), system, true),
+                ("/logix/defaults.toml".into(), defaults, true),
+                ("/logix/shadowed".into(), user, true),
+                ("/logix/system.toml".into(), system, true),
+                ("/logix/user.toml".into(), user, true),
+            ]
+        );
+
+        assert_eq!(
+            fs.read_dir("/logix/config.toml".as_ref()).unwrap_err(),
+            Error::NotADirectory {
+                path: "/logix/config.toml".into(),
+                source: None
+            }
+        );
+    }
+
+    #[test]
+    fn lower_layer_errors() {
+        let mut defaults = MemFs::with_files(&[
+            ("/logix/defaults.toml", "defaults"),
+            ("/logix/private/secret.toml", "defaults"),
+        ]);
+        defaults.set_permissions("/logix/private", 0o700).unwrap();
+        defaults.set_user(User::new(1000, 1000));
+
+        let mut fs = OverlayFs::new();
+        fs.push_layer(defaults);
+        fs.push_layer(MemFs::with_files(&[
+            ("/logix/defaults.toml/file", "user"),
+            ("/logix/private/user.toml", "user"),
+        ]));
+
+        let entries: Vec<_> = fs
+            .read_dir("/logix/defaults.toml".as_ref())
+            .unwrap()
+            .map(|e| e.unwrap().path().to_path_buf())
+            .collect();
+        assert_eq!(entries, [PathBuf::from("/logix/defaults.toml/file")]);
+
+        assert_eq!(
+            fs.read_dir("/logix/private".as_ref()).unwrap_err(),
+            Error::AccessDenied {
+                path: "/logix/private".into(),
+                source: None
+            }
+        );
+    }
+
+    #[test]
+    fn mixed_layers() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir(tmp.path().join("logix")).unwrap();
+        std::fs::write(tmp.path().join("logix/config.toml"), "user").unwrap();
+
+        let mut fs = OverlayFs::<Arc<dyn DynLogixVfs>>::new();
+        let defaults = fs.push_layer(Arc::new(MemFs::with_files(&[
+            ("/logix/config.toml", "defaults"),
+            ("/logix/defaults.toml", "defaults"),
+        ])));
+        let user = fs.push_layer(Arc::new(RelFs::new(tmp.path())));
+
+        assert_eq!(
+            fs.read_to_string("/logix/config.toml".as_ref()).unwrap(),
+            "user"
+        );
+
+        let entries: Vec<_> = fs
+            .read_dir("/logix".as_ref())
+            .unwrap()
+            .map(|e| {
+                let e = e.unwrap();
+                (e.path().to_path_buf(), e.layer())
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("/logix/config.toml".into(), user),
+                ("/logix/defaults.toml".into(), defaults),
+            ]
+        );
+    }
+}
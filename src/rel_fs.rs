@@ -1,14 +1,40 @@
 use std::{
+    borrow::Cow,
+    collections::VecDeque,
     fs::File,
+    io::Write,
     path::{Path, PathBuf},
 };
 
-use crate::{utils::PathUtil, Error, LogixVfs};
+#[cfg(unix)]
+use crate::dir_fd::DirFd;
+use crate::{
+    utils::{push_parts, temp_name, Part, PathUtil, MAX_SYMLINKS},
+    Error, FileType, IoError, LogixVfs, LogixVfsDirEntry, LogixVfsMut, Metadata, Operation,
+};
+
+/// Controls how [RelFs] treats symbolic links found while resolving a path
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum SymlinkPolicy {
+    /// Refuse to traverse any symbolic link, reported as [Error::AccessDenied]
+    Deny,
+    /// Follow symbolic links as long as the target stays inside the root,
+    /// anything else is reported as [Error::PathOutsideBounds]. Only a file
+    /// system opened with [RelFs::open_anchored] enforces this without races
+    /// against links being swapped in concurrently.
+    #[default]
+    FollowWithinRoot,
+    /// Follow all symbolic links, even if they point outside the root
+    FollowAll,
+}
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 pub struct RelFs {
     root: PathBuf,
     cur_dir: PathBuf,
+    symlinks: SymlinkPolicy,
+    #[cfg(unix)]
+    anchor: Option<DirFd>,
 }
 
 impl RelFs {
@@ -16,11 +42,46 @@ impl RelFs {
         Self {
             root: root.into(),
             cur_dir: PathBuf::new(),
+            symlinks: SymlinkPolicy::default(),
+            #[cfg(unix)]
+            anchor: None,
         }
     }
 
+    /// Open `root` once and perform all lookups relative to the opened
+    /// directory. Renaming or replacing the root afterwards does not change
+    /// what this file system sees, it keeps reading the tree it started with.
+    #[cfg(unix)]
+    pub fn open_anchored(root: impl Into<PathBuf>) -> Result<Self, Error> {
+        let root = root.into();
+        let anchor = DirFd::open(&root).map_err(|e| IoError::new(Operation::OpenFile, &root, e))?;
+        Ok(Self {
+            anchor: Some(anchor),
+            ..Self::new(root)
+        })
+    }
+
+    /// Returns true if the file system was created by [RelFs::open_anchored]
+    pub fn is_anchored(&self) -> bool {
+        #[cfg(unix)]
+        return self.anchor.is_some();
+        #[cfg(not(unix))]
+        return false;
+    }
+
+    /// Change how symbolic links are treated, the default is
+    /// [SymlinkPolicy::FollowWithinRoot]
+    pub fn with_symlink_policy(mut self, policy: SymlinkPolicy) -> Self {
+        self.symlinks = policy;
+        self
+    }
+
+    pub fn symlink_policy(&self) -> SymlinkPolicy {
+        self.symlinks
+    }
+
     pub fn chdir(&mut self, path: impl AsRef<Path>) -> Result<&Path, Error> {
-        self.cur_dir = self.resolve_path(true, path)?;
+        self.cur_dir = self.resolve_symlinks(path.as_ref())?;
         Ok(&self.cur_dir)
     }
 
@@ -31,65 +92,600 @@ impl RelFs {
         }
         .resolve_path(relative, path.as_ref())
     }
+
+    /// Convert an error from the operation `op` on `rel`, recording the full
+    /// path on disk next to the path given by the caller
+    fn io_error<'a>(
+        &'a self,
+        op: Operation,
+        path: &'a Path,
+        rel: &'a Path,
+    ) -> impl FnOnce(std::io::Error) -> Error + 'a {
+        move |e| {
+            IoError::new(op, path, e)
+                .with_resolved(self.root.join(rel))
+                .into()
+        }
+    }
+
+    fn is_symlink(&self, rel: &Path) -> std::io::Result<bool> {
+        #[cfg(unix)]
+        if let Some(anchor) = &self.anchor {
+            return anchor.is_symlink(rel);
+        }
+        Ok(self
+            .root
+            .join(rel)
+            .symlink_metadata()?
+            .file_type()
+            .is_symlink())
+    }
+
+    fn read_link_at(&self, rel: &Path) -> std::io::Result<PathBuf> {
+        #[cfg(unix)]
+        if let Some(anchor) = &self.anchor {
+            return anchor.read_link(rel);
+        }
+        self.root.join(rel).read_link()
+    }
+
+    fn canonical_root(&self) -> std::io::Result<Cow<'_, Path>> {
+        #[cfg(unix)]
+        if let Some(anchor) = &self.anchor {
+            return Ok(Cow::Borrowed(anchor.canonical_root()));
+        }
+        Ok(Cow::Owned(self.root.canonicalize()?))
+    }
+
+    /// Resolve `path` to a path relative to the root, following symbolic
+    /// links one component at a time according to the symlink policy. The
+    /// part of the path that does not exist is resolved lexically.
+    fn resolve_symlinks(&self, path: &Path) -> Result<PathBuf, Error> {
+        if self.symlinks == SymlinkPolicy::FollowAll {
+            return self.resolve_path(true, path);
+        }
+        self.follow_symlinks(self.path_parts(path)?, path)
+    }
+
+    /// Like [RelFs::resolve_symlinks] but the last component is not followed,
+    /// used when the operation affects the link itself
+    fn resolve_parent_symlinks(&self, path: &Path) -> Result<PathBuf, Error> {
+        let rel = if self.symlinks == SymlinkPolicy::FollowAll {
+            self.resolve_path(true, path)?
+        } else {
+            let mut pending = self.path_parts(path)?;
+            match pending.pop_back() {
+                Some(Part::Name(name)) => {
+                    return Ok(self.follow_symlinks(pending, path)?.join(name))
+                }
+                Some(Part::Parent) => {
+                    pending.push_back(Part::Parent);
+                    self.follow_symlinks(pending, path)?
+                }
+                None => PathBuf::new(),
+            }
+        };
+        match (rel.parent(), rel.file_name()) {
+            (Some(parent), Some(name)) => Ok(parent.join(name)),
+            _ => Err(Error::InvalidPath {
+                path: path.to_path_buf(),
+                source: None,
+            }),
+        }
+    }
+
+    /// Split `path` into the parts to resolve from the root, relative paths
+    /// start from the current directory
+    fn path_parts(&self, path: &Path) -> Result<VecDeque<Part>, Error> {
+        let mut pending = VecDeque::new();
+        push_parts(&mut pending, path, path)?;
+        if !path.has_root() {
+            push_parts(&mut pending, &self.cur_dir, path)?;
+        }
+        Ok(pending)
+    }
+
+    /// Follow the symbolic links in `pending`, the parts of a path relative
+    /// to the root, while `path` is the path reported in errors. Like POSIX a
+    /// `..` is applied after the links before it are followed.
+    ///
+    /// NOTE(2026.10): Unless the file system is anchored, each component is
+    /// checked by path and the operation then opens the resolved path again,
+    /// a link swapped in between can still escape the root. Only
+    /// [RelFs::open_anchored] is free of this race.
+    fn follow_symlinks(&self, mut pending: VecDeque<Part>, path: &Path) -> Result<PathBuf, Error> {
+        let mut ret = PathBuf::new();
+        let mut canonical_root = None;
+        let mut followed = 0;
+        let mut missing = false;
+
+        while let Some(part) = pending.pop_front() {
+            let name = match part {
+                Part::Parent => {
+                    if !ret.pop() {
+                        return Err(Error::PathOutsideBounds {
+                            path: path.to_path_buf(),
+                        });
+                    }
+                    // NOTE(2026.10): Going back up may leave the missing part, check the rest again
+                    missing = false;
+                    continue;
+                }
+                Part::Name(name) => name,
+            };
+
+            let rel_path = ret.join(&name);
+            let is_symlink = !missing
+                && match self.is_symlink(&rel_path) {
+                    Ok(is_symlink) => is_symlink,
+                    Err(_) => {
+                        // NOTE(2026.10): Let the actual operation report why the path is unusable
+                        missing = true;
+                        false
+                    }
+                };
+
+            if !is_symlink {
+                ret.push(name);
+                continue;
+            }
+
+            if self.symlinks == SymlinkPolicy::Deny {
+                return Err(Error::AccessDenied {
+                    path: path.to_path_buf(),
+                    source: None,
+                });
+            }
+
+            followed += 1;
+            if followed > MAX_SYMLINKS {
+                return Err(Error::TooManySymlinks {
+                    path: path.to_path_buf(),
+                    source: None,
+                });
+            }
+
+            let target = self.read_link_at(&rel_path).map_err(self.io_error(
+                Operation::ReadLink,
+                path,
+                &rel_path,
+            ))?;
+
+            if target.has_root() {
+                let canonical_root = match &canonical_root {
+                    Some(root) => root,
+                    None => canonical_root.insert(self.canonical_root().map_err(|e| {
+                        IoError::new(Operation::Canonicalize, path, e).with_resolved(&self.root)
+                    })?),
+                };
+                let inner = target
+                    .strip_prefix(canonical_root)
+                    .or_else(|_| target.strip_prefix(&self.root))
+                    .map_err(|_| Error::PathOutsideBounds {
+                        path: path.to_path_buf(),
+                    })?;
+                ret.clear();
+                push_parts(&mut pending, inner, path)?;
+            } else {
+                push_parts(&mut pending, &target, path)?;
+            }
+        }
+
+        Ok(ret)
+    }
+
+    /// Whether the final open should follow links, when links are resolved
+    /// by [RelFs::resolve_symlinks] the resolved path is opened without
+    /// following any links
+    fn follow_on_open(&self) -> bool {
+        self.symlinks == SymlinkPolicy::FollowAll
+    }
+}
+
+#[cfg(unix)]
+fn file_type_from_rustix(ty: rustix::fs::FileType) -> FileType {
+    use rustix::fs::FileType as Ty;
+    match ty {
+        Ty::RegularFile => FileType::File,
+        Ty::Directory => FileType::Dir,
+        Ty::Symlink => FileType::Symlink,
+        _ => FileType::Other,
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct DirEntry {
+    path: PathBuf,
+    ty: FileType,
+}
+
+impl LogixVfsDirEntry for DirEntry {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn is_dir(&self) -> bool {
+        self.ty == FileType::Dir
+    }
+
+    fn is_file(&self) -> bool {
+        self.ty == FileType::File
+    }
+
+    fn is_symlink(&self) -> bool {
+        self.ty == FileType::Symlink
+    }
 }
 
+#[derive(Debug)]
+enum ReadDirIter {
+    Std(std::fs::ReadDir),
+    #[cfg(unix)]
+    Fd(rustix::fs::Dir),
+}
+
+/// Iterates the entries of a directory, like [std::fs::ReadDir] the path of
+/// each entry is the directory path given by the caller joined with the name
+/// of the entry, so it can be passed back to the same file system
 #[derive(Debug)]
 pub struct ReadDir {
     path: PathBuf,
-    prefix: PathBuf,
-    it: std::fs::ReadDir,
+    it: ReadDirIter,
 }
 
 impl Iterator for ReadDir {
-    type Item = Result<PathBuf, Error>;
+    type Item = Result<DirEntry, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        Some(match self.it.next()? {
-            Ok(entry) => {
-                let full_path = entry.path();
-                full_path
-                    .strip_prefix(&self.prefix)
-                    .map_err(|e| {
-                        // NOTE(2024.02): This should not happen, at least I don't know how to trigger it
-                        Error::Other(format!(
-                            "Failed to strip prefix {:?} off {full_path:?}: {e}",
-                            self.prefix
+        let (name, ty) = match &mut self.it {
+            ReadDirIter::Std(it) => match it.next()? {
+                Ok(entry) => match entry.file_type() {
+                    Ok(ty) => (entry.file_name(), FileType::from(ty)),
+                    Err(e) => {
+                        return Some(Err(IoError::new(Operation::ReadDir, &self.path, e).into()))
+                    }
+                },
+                Err(e) => return Some(Err(IoError::new(Operation::ReadDir, &self.path, e).into())),
+            },
+            #[cfg(unix)]
+            ReadDirIter::Fd(it) => loop {
+                use std::os::unix::ffi::OsStrExt;
+
+                let entry = match it.next()? {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        return Some(Err(
+                            IoError::new(Operation::ReadDir, &self.path, e.into()).into()
                         ))
-                    })
-                    .map(|p| p.to_path_buf())
-            }
-            Err(e) => Err(Error::from_io(self.path.clone(), e)),
-        })
+                    }
+                };
+                let name = entry.file_name();
+                if matches!(name.to_bytes(), b"." | b"..") {
+                    continue;
+                }
+                let ty = match entry.file_type() {
+                    rustix::fs::FileType::Unknown => {
+                        match it
+                            .fd()
+                            .map_err(std::io::Error::from)
+                            .and_then(|fd| DirFd::file_type_at(fd, name))
+                        {
+                            Ok(ty) => ty,
+                            Err(e) => {
+                                return Some(Err(
+                                    IoError::new(Operation::ReadDir, &self.path, e).into()
+                                ))
+                            }
+                        }
+                    }
+                    ty => ty,
+                };
+                break (
+                    std::ffi::OsStr::from_bytes(name.to_bytes()).to_owned(),
+                    file_type_from_rustix(ty),
+                );
+            },
+        };
+
+        Some(Ok(DirEntry {
+            path: self.path.join(name),
+            ty,
+        }))
     }
 }
 
 impl LogixVfs for RelFs {
     type RoFile = File;
-    type DirEntry = PathBuf;
+    type DirEntry = DirEntry;
     type ReadDir = ReadDir;
 
     fn canonicalize_path(&self, path: &Path) -> Result<PathBuf, Error> {
-        self.resolve_path(true, path)
+        self.resolve_symlinks(path)
     }
 
     fn open_file(&self, path: &Path) -> Result<Self::RoFile, Error> {
-        let full_path = self.resolve_path(false, path)?;
-        File::open(full_path).map_err(|e| Error::from_io(path.to_path_buf(), e))
+        let rel = self.resolve_symlinks(path)?;
+
+        #[cfg(unix)]
+        let file = match &self.anchor {
+            Some(anchor) => anchor
+                .open_beneath(&rel, rustix::fs::OFlags::RDONLY, self.follow_on_open())
+                .map(File::from),
+            None => File::open(self.root.join(&rel)),
+        };
+        #[cfg(not(unix))]
+        let file = File::open(self.root.join(&rel));
+
+        let file = file.map_err(self.io_error(Operation::OpenFile, path, &rel))?;
+
+        // NOTE(2026.10): Opening a directory succeeds on most platforms, only reading it fails
+        match file.metadata() {
+            Ok(meta) if !meta.is_file() => Err(Error::NotAFile {
+                path: path.to_path_buf(),
+            }),
+            Ok(_) => Ok(file),
+            Err(e) => Err(self.io_error(Operation::Metadata, path, &rel)(e)),
+        }
     }
 
     fn read_dir(&self, path: &Path) -> Result<Self::ReadDir, Error> {
-        let full_path = self.resolve_path(false, path)?;
-        let it = full_path
-            .read_dir()
-            .map_err(|e| Error::from_io(path.to_path_buf(), e))?;
+        let rel = self.resolve_symlinks(path)?;
+
+        #[cfg(unix)]
+        if let Some(anchor) = &self.anchor {
+            use rustix::fs::OFlags;
+
+            let it = anchor
+                .open_beneath(
+                    &rel,
+                    OFlags::RDONLY | OFlags::DIRECTORY,
+                    self.follow_on_open(),
+                )
+                .and_then(|fd| Ok(rustix::fs::Dir::new(fd)?))
+                .map_err(self.io_error(Operation::ReadDir, path, &rel))?;
+            return Ok(ReadDir {
+                path: path.to_path_buf(),
+                it: ReadDirIter::Fd(it),
+            });
+        }
+
+        let it = self.root.join(&rel).read_dir().map_err(self.io_error(
+            Operation::ReadDir,
+            path,
+            &rel,
+        ))?;
         Ok(ReadDir {
             path: path.to_path_buf(),
-            prefix: full_path,
-            it,
+            it: ReadDirIter::Std(it),
+        })
+    }
+
+    fn metadata(&self, path: &Path) -> Result<Metadata, Error> {
+        let rel = self.resolve_symlinks(path)?;
+
+        #[cfg(unix)]
+        if let Some(anchor) = &self.anchor {
+            return anchor
+                .metadata(&rel, self.follow_on_open())
+                .map(Metadata::from)
+                .map_err(self.io_error(Operation::Metadata, path, &rel));
+        }
+
+        std::fs::metadata(self.root.join(&rel))
+            .map(Metadata::from)
+            .map_err(self.io_error(Operation::Metadata, path, &rel))
+    }
+
+    fn read_link(&self, path: &Path) -> Result<PathBuf, Error> {
+        let rel = self.resolve_parent_symlinks(path)?;
+        self.read_link_at(&rel).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::InvalidInput {
+                Error::InvalidPath {
+                    path: path.to_path_buf(),
+                    source: None,
+                }
+            } else {
+                self.io_error(Operation::ReadLink, path, &rel)(e)
+            }
         })
     }
 }
 
+pub(crate) fn write_atomic(full_path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let (Some(dir), Some(name)) = (full_path.parent(), full_path.file_name()) else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("The path {full_path:?} does not have a parent directory"),
+        ));
+    };
+    let tmp = dir.join(temp_name(name));
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&tmp)?;
+
+    let res = (|| {
+        match std::fs::metadata(full_path) {
+            Ok(meta) => file.set_permissions(meta.permissions())?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        file.write_all(data)?;
+        std::fs::rename(&tmp, full_path)
+    })();
+
+    if res.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    res
+}
+
+/// Writes are resolved like reads so they can not escape the root. Files are
+/// replaced by writing to a temporary file that is renamed into place, so
+/// readers never see a partially written file.
+impl LogixVfsMut for RelFs {
+    fn write_file(&mut self, path: &Path, data: &[u8]) -> Result<(), Error> {
+        let rel = self.resolve_symlinks(path)?;
+        if rel.as_os_str().is_empty() {
+            return Err(Error::IsADirectory {
+                path: path.to_path_buf(),
+                source: None,
+            });
+        }
+
+        #[cfg(unix)]
+        if let Some(anchor) = &self.anchor {
+            return anchor
+                .write_atomic(&rel, data, self.follow_on_open())
+                .map_err(self.io_error(Operation::WriteFile, path, &rel));
+        }
+
+        write_atomic(&self.root.join(&rel), data).map_err(self.io_error(
+            Operation::WriteFile,
+            path,
+            &rel,
+        ))
+    }
+
+    fn append_file(&mut self, path: &Path, data: &[u8]) -> Result<(), Error> {
+        let rel = self.resolve_symlinks(path)?;
+        if rel.as_os_str().is_empty() {
+            return Err(Error::IsADirectory {
+                path: path.to_path_buf(),
+                source: None,
+            });
+        }
+
+        #[cfg(unix)]
+        if let Some(anchor) = &self.anchor {
+            return anchor
+                .append(&rel, data, self.follow_on_open())
+                .map_err(self.io_error(Operation::AppendFile, path, &rel));
+        }
+
+        std::fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(self.root.join(&rel))
+            .and_then(|mut file| file.write_all(data))
+            .map_err(self.io_error(Operation::AppendFile, path, &rel))
+    }
+
+    fn create_dir(&mut self, path: &Path) -> Result<(), Error> {
+        let rel = self.resolve_parent_symlinks(path)?;
+
+        #[cfg(unix)]
+        if let Some(anchor) = &self.anchor {
+            return anchor
+                .create_dir(&rel, self.follow_on_open())
+                .map_err(self.io_error(Operation::CreateDir, path, &rel));
+        }
+
+        std::fs::create_dir(self.root.join(&rel)).map_err(self.io_error(
+            Operation::CreateDir,
+            path,
+            &rel,
+        ))
+    }
+
+    fn create_dir_all(&mut self, path: &Path) -> Result<(), Error> {
+        let rel = self.resolve_symlinks(path)?;
+
+        #[cfg(unix)]
+        if let Some(anchor) = &self.anchor {
+            let mut cur = PathBuf::new();
+            for name in rel.iter() {
+                cur.push(name);
+                match anchor.create_dir(&cur, self.follow_on_open()) {
+                    Ok(()) => {}
+                    Err(e)
+                        if e.kind() == std::io::ErrorKind::AlreadyExists
+                            && anchor
+                                .metadata(&cur, self.follow_on_open())
+                                .is_ok_and(|m| m.is_dir()) => {}
+                    Err(e) => return Err(self.io_error(Operation::CreateDir, path, &cur)(e)),
+                }
+            }
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(self.root.join(&rel)).map_err(self.io_error(
+            Operation::CreateDir,
+            path,
+            &rel,
+        ))
+    }
+
+    fn remove_file(&mut self, path: &Path) -> Result<(), Error> {
+        let rel = self.resolve_parent_symlinks(path)?;
+
+        #[cfg(unix)]
+        if let Some(anchor) = &self.anchor {
+            return anchor
+                .remove_file(&rel, self.follow_on_open())
+                .map_err(self.io_error(Operation::RemoveFile, path, &rel));
+        }
+
+        std::fs::remove_file(self.root.join(&rel)).map_err(self.io_error(
+            Operation::RemoveFile,
+            path,
+            &rel,
+        ))
+    }
+
+    fn remove_dir(&mut self, path: &Path) -> Result<(), Error> {
+        let rel = self.resolve_parent_symlinks(path)?;
+
+        #[cfg(unix)]
+        if let Some(anchor) = &self.anchor {
+            return anchor
+                .remove_dir(&rel, self.follow_on_open())
+                .map_err(self.io_error(Operation::RemoveDir, path, &rel));
+        }
+
+        std::fs::remove_dir(self.root.join(&rel)).map_err(self.io_error(
+            Operation::RemoveDir,
+            path,
+            &rel,
+        ))
+    }
+
+    fn remove_dir_all(&mut self, path: &Path) -> Result<(), Error> {
+        let rel = self.resolve_parent_symlinks(path)?;
+
+        #[cfg(unix)]
+        if let Some(anchor) = &self.anchor {
+            return anchor
+                .remove_dir_all(&rel, self.follow_on_open())
+                .map_err(self.io_error(Operation::RemoveDir, path, &rel));
+        }
+
+        std::fs::remove_dir_all(self.root.join(&rel)).map_err(self.io_error(
+            Operation::RemoveDir,
+            path,
+            &rel,
+        ))
+    }
+
+    fn rename(&mut self, from: &Path, to: &Path) -> Result<(), Error> {
+        let rel_from = self.resolve_parent_symlinks(from)?;
+        let rel_to = self.resolve_parent_symlinks(to)?;
+
+        #[cfg(unix)]
+        if let Some(anchor) = &self.anchor {
+            return anchor
+                .rename(&rel_from, &rel_to, self.follow_on_open())
+                .map_err(self.io_error(Operation::Rename, from, &rel_from));
+        }
+
+        std::fs::rename(self.root.join(&rel_from), self.root.join(rel_to)).map_err(self.io_error(
+            Operation::Rename,
+            from,
+            &rel_from,
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,10 +782,25 @@ mod tests {
 
         assert_eq!(fs.open_file("lib.rs".as_ref()).err(), None);
         assert_eq!(
-            fs.open_file("not-lib.rs".as_ref()).err(),
-            Some(Error::NotFound {
-                path: "not-lib.rs".into()
-            })
+            fs.open_file("not-lib.rs".as_ref())
+                .err()
+                .map(Error::without_source),
+            Some(Error::not_found("not-lib.rs"))
+        );
+        assert_eq!(
+            fs.open_file("lib.r".as_ref())
+                .unwrap_err()
+                .with_suggestions(&fs)
+                .without_source(),
+            Error::NotFound {
+                path: "lib.r".into(),
+                suggestions: vec!["lib.rs".into()],
+                source: None,
+            }
+        );
+        assert_eq!(
+            fs.open_file(".".as_ref()).err(),
+            Some(Error::NotAFile { path: ".".into() })
         );
         assert_eq!(
             fs.open_file("../outside.txt".as_ref()).err(),
@@ -213,16 +824,349 @@ mod tests {
         );
 
         assert_eq!(
-            fs.read_dir("lib.rs".as_ref()).err(),
+            fs.read_dir("lib.rs".as_ref())
+                .err()
+                .map(Error::without_source),
             Some(Error::NotADirectory {
-                path: "lib.rs".into()
+                path: "lib.rs".into(),
+                source: None
+            })
+        );
+        assert_eq!(
+            fs.read_dir("not-lib.rs".as_ref())
+                .err()
+                .map(Error::without_source),
+            Some(Error::not_found("not-lib.rs"))
+        );
+    }
+
+    #[cfg(unix)]
+    fn hostile_tree() -> tempfile::TempDir {
+        use std::os::unix::fs::symlink;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("root");
+        std::fs::create_dir_all(root.join("dir/sub")).unwrap();
+        std::fs::write(tmp.path().join("secret.txt"), "secret").unwrap();
+        std::fs::write(root.join("dir/file.txt"), "inside").unwrap();
+
+        symlink(tmp.path(), root.join("abs-escape")).unwrap();
+        symlink("../secret.txt", root.join("rel-escape")).unwrap();
+        symlink("sub/../../../secret.txt", root.join("dir/deep-escape")).unwrap();
+        symlink("dir/file.txt", root.join("inside")).unwrap();
+        symlink(root.join("dir"), root.join("abs-inside")).unwrap();
+        symlink("../file.txt", root.join("dir/sub/up")).unwrap();
+        symlink("dir/sub", root.join("sub-link")).unwrap();
+        symlink("loop-b", root.join("loop-a")).unwrap();
+        symlink("loop-a", root.join("loop-b")).unwrap();
+        tmp
+    }
+
+    #[cfg(unix)]
+    fn check_symlinks_within_root(fs: &RelFs) {
+        let read = |path: &str| fs.read_to_string(path.as_ref());
+
+        assert_eq!(read("inside").unwrap(), "inside");
+        assert_eq!(read("abs-inside/file.txt").unwrap(), "inside");
+        assert_eq!(read("dir/sub/up").unwrap(), "inside");
+        assert_eq!(
+            fs.canonicalize_path("dir/sub/up".as_ref()),
+            Ok("dir/file.txt".into())
+        );
+
+        // NOTE(2026.10): `..` applies to the target of the link, not the link itself
+        assert_eq!(read("sub-link/../file.txt").unwrap(), "inside");
+        assert_eq!(
+            fs.canonicalize_path("sub-link/..".as_ref()),
+            Ok("dir".into())
+        );
+
+        for path in [
+            "abs-escape/secret.txt",
+            "rel-escape",
+            "dir/deep-escape",
+            "abs-escape/root/dir/file.txt",
+            "nope/../abs-escape/secret.txt",
+            "nope/../rel-escape",
+        ] {
+            assert_eq!(
+                read(path),
+                Err(Error::PathOutsideBounds { path: path.into() }),
+                "{path:?}"
+            );
+        }
+
+        assert_eq!(
+            fs.read_dir("abs-escape".as_ref()).err(),
+            Some(Error::PathOutsideBounds {
+                path: "abs-escape".into()
+            })
+        );
+        assert!(fs.read_dir("abs-inside".as_ref()).is_ok());
+
+        // NOTE(2026.10): Going back up from a missing directory is checked like any other path
+        let path = "nope/../abs-escape";
+        assert_eq!(
+            fs.read_dir(path.as_ref()).err(),
+            Some(Error::PathOutsideBounds { path: path.into() })
+        );
+        assert_eq!(
+            fs.metadata(path.as_ref()),
+            Err(Error::PathOutsideBounds { path: path.into() })
+        );
+        assert_eq!(
+            fs.canonicalize_path(path.as_ref()),
+            Err(Error::PathOutsideBounds { path: path.into() })
+        );
+
+        assert_eq!(
+            read("loop-a"),
+            Err(Error::TooManySymlinks {
+                path: "loop-a".into(),
+                source: None
+            })
+        );
+        assert_eq!(
+            read("dir/missing.txt").map_err(Error::without_source),
+            Err(Error::not_found("dir/missing.txt"))
+        );
+
+        assert_eq!(
+            fs.read_link("dir/sub/up".as_ref()),
+            Ok("../file.txt".into())
+        );
+        assert_eq!(
+            fs.read_link("rel-escape".as_ref()),
+            Ok("../secret.txt".into())
+        );
+        assert_eq!(
+            fs.read_link("dir/file.txt".as_ref()),
+            Err(Error::InvalidPath {
+                path: "dir/file.txt".into(),
+                source: None
+            })
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_within_root() {
+        let tmp = hostile_tree();
+        check_symlinks_within_root(&RelFs::new(tmp.path().join("root")));
+        check_symlinks_within_root(&RelFs::open_anchored(tmp.path().join("root")).unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn anchored_survives_root_rename() {
+        use std::io::Read;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("root");
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("sub/config.toml"), "original").unwrap();
+
+        let mut fs = RelFs::open_anchored(&root).unwrap();
+        assert!(fs.is_anchored());
+        fs.chdir("sub").unwrap();
+
+        std::fs::rename(&root, tmp.path().join("moved")).unwrap();
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("sub/config.toml"), "replaced").unwrap();
+        std::fs::write(root.join("sub/extra.toml"), "replaced").unwrap();
+
+        let mut data = String::new();
+        fs.open_file("config.toml".as_ref())
+            .unwrap()
+            .read_to_string(&mut data)
+            .unwrap();
+        assert_eq!(data, "original");
+
+        let entries: Vec<_> = fs
+            .read_dir("".as_ref())
+            .unwrap()
+            .map(|e| e.unwrap().path().to_path_buf())
+            .collect();
+        assert_eq!(entries, [PathBuf::from("config.toml")]);
+
+        assert_eq!(
+            fs.open_file("extra.toml".as_ref())
+                .err()
+                .map(Error::without_source),
+            Some(Error::not_found("extra.toml"))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_dir_entries() {
+        let tmp = hostile_tree();
+        for fs in [
+            RelFs::new(tmp.path().join("root")),
+            RelFs::open_anchored(tmp.path().join("root")).unwrap(),
+        ] {
+            let mut entries: Vec<_> = fs
+                .read_dir("abs-inside".as_ref())
+                .unwrap()
+                .map(|e| e.unwrap())
+                .map(|e| {
+                    (
+                        e.path().to_path_buf(),
+                        e.is_dir(),
+                        e.is_file(),
+                        e.is_symlink(),
+                    )
+                })
+                .collect();
+            entries.sort();
+            assert_eq!(
+                entries,
+                [
+                    ("abs-inside/deep-escape".into(), false, false, true),
+                    ("abs-inside/file.txt".into(), false, true, false),
+                    ("abs-inside/sub".into(), true, false, false),
+                ]
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn metadata() {
+        let tmp = hostile_tree();
+        for fs in [
+            RelFs::new(tmp.path().join("root")),
+            RelFs::open_anchored(tmp.path().join("root")).unwrap(),
+        ] {
+            let meta = fs.metadata("inside".as_ref()).unwrap();
+            assert_eq!(meta.file_type(), FileType::File);
+            assert_eq!(meta.len(), 6);
+            assert!(meta.modified().is_some());
+            assert!(meta.changed().is_some());
+            assert_eq!(meta.mode().map(|m| m & 0o170000), Some(0o100000));
+            assert!(!meta.readonly());
+
+            assert!(fs.metadata("abs-inside".as_ref()).unwrap().is_dir());
+            assert_eq!(
+                fs.metadata("rel-escape".as_ref()).err(),
+                Some(Error::PathOutsideBounds {
+                    path: "rel-escape".into()
+                })
+            );
+            assert_eq!(
+                fs.metadata("missing".as_ref())
+                    .err()
+                    .map(Error::without_source),
+                Some(Error::not_found("missing"))
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn mutations() {
+        let tmp = hostile_tree();
+        let root = tmp.path().join("root");
+        for (i, mut fs) in [RelFs::new(&root), RelFs::open_anchored(&root).unwrap()]
+            .into_iter()
+            .enumerate()
+        {
+            let dir = PathBuf::from(format!("new-{i}"));
+            fs.create_dir_all(&dir.join("a/b")).unwrap();
+            fs.write_file(&dir.join("a/b/c.toml"), b"x = 1\n").unwrap();
+            fs.write_file(&dir.join("a/b/c.toml"), b"x = 2\n").unwrap();
+            fs.append_file(&dir.join("a/b/c.toml"), b"y = 3\n").unwrap();
+            assert_eq!(
+                std::fs::read_to_string(root.join(&dir).join("a/b/c.toml")).unwrap(),
+                "x = 2\ny = 3\n"
+            );
+            assert_eq!(
+                std::fs::read_dir(root.join(&dir).join("a/b"))
+                    .unwrap()
+                    .count(),
+                1
+            );
+
+            fs.rename(&dir.join("a/b/c.toml"), &dir.join("a/c.toml"))
+                .unwrap();
+            fs.remove_dir(&dir.join("a/b")).unwrap();
+            fs.create_dir(&dir.join("a/d")).unwrap();
+            assert!(fs.create_dir(&dir.join("a/d")).is_err());
+            fs.remove_file(&dir.join("a/c.toml")).unwrap();
+            fs.remove_dir_all(&dir).unwrap();
+            assert!(!root.join(&dir).exists());
+
+            // Writing through a link updates the target inside the root
+            fs.write_file("inside".as_ref(), b"updated").unwrap();
+            assert_eq!(
+                std::fs::read_to_string(root.join("dir/file.txt")).unwrap(),
+                "updated"
+            );
+            assert!(root.join("inside").is_symlink());
+
+            for path in [
+                "rel-escape",
+                "abs-escape/secret.txt",
+                "../outside.txt",
+                "nope/../abs-escape/secret.txt",
+                "nope/../abs-escape/new.txt",
+            ] {
+                assert_eq!(
+                    fs.write_file(path.as_ref(), b"pwned").err(),
+                    Some(Error::PathOutsideBounds { path: path.into() }),
+                    "{path:?}"
+                );
+            }
+            assert_eq!(
+                fs.create_dir_all("abs-escape/new".as_ref()).err(),
+                Some(Error::PathOutsideBounds {
+                    path: "abs-escape/new".into()
+                })
+            );
+            assert_eq!(
+                std::fs::read_to_string(tmp.path().join("secret.txt")).unwrap(),
+                "secret"
+            );
+
+            // Removing a link removes the link, not what it points to
+            fs.write_file("victim".as_ref(), b"victim").unwrap();
+            std::os::unix::fs::symlink("victim", root.join("victim-link")).unwrap();
+            fs.remove_file("victim-link".as_ref()).unwrap();
+            assert!(root.join("victim").exists());
+            fs.remove_file("victim".as_ref()).unwrap();
+
+            // Like with std::fs::remove_dir_all, links are not followed
+            fs.remove_dir_all("abs-escape".as_ref()).unwrap();
+            assert!(tmp.path().join("secret.txt").exists());
+            std::os::unix::fs::symlink(tmp.path(), root.join("abs-escape")).unwrap();
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_policies() {
+        let tmp = hostile_tree();
+
+        let fs = RelFs::new(tmp.path().join("root")).with_symlink_policy(SymlinkPolicy::Deny);
+        assert!(fs.open_file("dir/file.txt".as_ref()).is_ok());
+        assert_eq!(
+            fs.open_file("inside".as_ref()).err(),
+            Some(Error::AccessDenied {
+                path: "inside".into(),
+                source: None
             })
         );
         assert_eq!(
-            fs.read_dir("not-lib.rs".as_ref()).err(),
-            Some(Error::NotFound {
-                path: "not-lib.rs".into()
+            fs.read_dir("abs-inside".as_ref()).err(),
+            Some(Error::AccessDenied {
+                path: "abs-inside".into(),
+                source: None
             })
         );
+
+        let fs = RelFs::new(tmp.path().join("root")).with_symlink_policy(SymlinkPolicy::FollowAll);
+        assert!(fs.open_file("inside".as_ref()).is_ok());
+        assert!(fs.open_file("rel-escape".as_ref()).is_ok());
+        assert!(fs.open_file("abs-escape/secret.txt".as_ref()).is_ok());
     }
 }
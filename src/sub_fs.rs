@@ -0,0 +1,439 @@
+use std::path::{Path, PathBuf};
+
+use crate::{utils::PathUtil, Error, LogixVfs, LogixVfsDirEntry, LogixVfsMut, Metadata};
+
+/// Limits any file system to a subdirectory, like a chroot. The
+/// subdirectory shows up as `/` and neither `..` nor symbolic links resolved
+/// by the wrapped file system can be used to leave it.
+///
+/// Paths are reported relative to the new root, including the paths in
+/// errors and directory entries, so nothing outside the subdirectory leaks
+/// through.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct SubFs<V: LogixVfs> {
+    inner: V,
+    root: PathBuf,
+    cur_dir: PathBuf,
+}
+
+impl<V: LogixVfs> SubFs<V> {
+    /// Re-root `inner` at `root`, which must be an existing directory
+    pub fn new(inner: V, root: impl AsRef<Path>) -> Result<Self, Error> {
+        let root = Path::new("/").join(inner.canonicalize_path(root.as_ref())?);
+        if !inner.metadata(&root)?.is_dir() {
+            return Err(Error::NotADirectory {
+                path: root,
+                source: None,
+            });
+        }
+        Ok(Self {
+            inner,
+            root,
+            cur_dir: PathBuf::new(),
+        })
+    }
+
+    /// The directory in the wrapped file system used as root
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn inner(&self) -> &V {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> V {
+        self.inner
+    }
+
+    pub fn chdir(&mut self, path: impl AsRef<Path>) -> Result<&Path, Error> {
+        let path = self.canonicalize_path(path.as_ref())?;
+        self.cur_dir = path.strip_prefix("/").unwrap_or(&path).to_path_buf();
+        Ok(&self.cur_dir)
+    }
+
+    fn resolve_path(&self, path: &Path) -> Result<PathBuf, Error> {
+        PathUtil {
+            root: "/".as_ref(),
+            cur_dir: &self.cur_dir,
+        }
+        .resolve_path(false, path)
+    }
+
+    fn inner_path(&self, path: &Path) -> PathBuf {
+        self.root.join(path.strip_prefix("/").unwrap_or(path))
+    }
+
+    /// Convert a path reported by the wrapped file system to a path below
+    /// the new root, returns `None` if it is outside of it
+    fn outer_path(&self, inner: &Path) -> Option<PathBuf> {
+        // NOTE(2026.10): Some file systems, such as RelFs, report paths relative to their root
+        let root = self.root.strip_prefix("/").unwrap_or(&self.root);
+        let inner = inner.strip_prefix("/").unwrap_or(inner);
+        inner
+            .strip_prefix(root)
+            .ok()
+            .map(|rest| Path::new("/").join(rest))
+    }
+
+    /// Rewrite the path in an error from the wrapped file system, paths
+    /// outside the root are replaced by `path` as given by the caller
+    fn map_err(&self, path: &Path, e: Error) -> Error {
+        e.map_path(|p| self.outer_path(&p).unwrap_or_else(|| path.to_path_buf()))
+    }
+
+    /// Resolve `path` to an absolute path in the wrapped file system, letting
+    /// it follow any symbolic links before checking that the result is
+    /// still below the root
+    fn resolve(&self, path: &Path) -> Result<PathBuf, Error> {
+        let full_path = self.inner_path(&self.resolve_path(path)?);
+        let canonical = self
+            .inner
+            .canonicalize_path(&full_path)
+            .map_err(|e| self.map_err(path, e))?;
+        if self.outer_path(&canonical).is_none() {
+            return Err(Error::PathOutsideBounds {
+                path: path.to_path_buf(),
+            });
+        }
+        Ok(Path::new("/").join(canonical))
+    }
+
+    /// Like [SubFs::resolve] but the last component is left as is, used
+    /// when the operation affects the entry itself
+    fn resolve_parent(&self, path: &Path) -> Result<PathBuf, Error> {
+        let full_path = self.resolve_path(path)?;
+        match (full_path.parent(), full_path.file_name()) {
+            (Some(parent), Some(name)) => Ok(self.resolve(parent)?.join(name)),
+            // NOTE(2026.10): The root is an entry of the directory above it, which is off limits
+            _ => Err(Error::InvalidPath {
+                path: path.to_path_buf(),
+                source: None,
+            }),
+        }
+    }
+}
+
+/// An entry in a directory listing of a [SubFs]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct DirEntry<E> {
+    path: PathBuf,
+    inner: E,
+}
+
+impl<E> DirEntry<E> {
+    /// The entry as returned by the wrapped file system
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+}
+
+impl<E: LogixVfsDirEntry> LogixVfsDirEntry for DirEntry<E> {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn is_dir(&self) -> bool {
+        self.inner.is_dir()
+    }
+
+    fn is_file(&self) -> bool {
+        self.inner.is_file()
+    }
+
+    fn is_symlink(&self) -> bool {
+        self.inner.is_symlink()
+    }
+}
+
+pub type ReadDir<E> = std::vec::IntoIter<Result<DirEntry<E>, Error>>;
+
+impl<V: LogixVfs> LogixVfs for SubFs<V> {
+    type RoFile = V::RoFile;
+    type DirEntry = DirEntry<V::DirEntry>;
+    type ReadDir = ReadDir<V::DirEntry>;
+
+    fn canonicalize_path(&self, path: &Path) -> Result<PathBuf, Error> {
+        let resolved = self.resolve(path)?;
+        Ok(self.outer_path(&resolved).unwrap_or_default())
+    }
+
+    fn open_file(&self, path: &Path) -> Result<Self::RoFile, Error> {
+        self.inner
+            .open_file(&self.resolve(path)?)
+            .map_err(|e| self.map_err(path, e))
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<Self::ReadDir, Error> {
+        let resolved = self.resolve(path)?;
+        let dir = self.outer_path(&resolved).unwrap_or_default();
+        let it = self
+            .inner
+            .read_dir(&resolved)
+            .map_err(|e| self.map_err(path, e))?;
+
+        Ok(it
+            .filter_map(|entry| match entry {
+                Ok(entry) => {
+                    let path = dir.join(entry.path().file_name()?);
+                    Some(Ok(DirEntry { path, inner: entry }))
+                }
+                Err(e) => Some(Err(self.map_err(path, e))),
+            })
+            .collect::<Vec<_>>()
+            .into_iter())
+    }
+
+    fn metadata(&self, path: &Path) -> Result<Metadata, Error> {
+        self.inner
+            .metadata(&self.resolve(path)?)
+            .map_err(|e| self.map_err(path, e))
+    }
+
+    fn read_link(&self, path: &Path) -> Result<PathBuf, Error> {
+        self.inner
+            .read_link(&self.resolve(path)?)
+            .map_err(|e| self.map_err(path, e))
+    }
+}
+
+impl<V: LogixVfsMut> LogixVfsMut for SubFs<V> {
+    fn write_file(&mut self, path: &Path, data: &[u8]) -> Result<(), Error> {
+        let resolved = self.resolve(path)?;
+        self.inner
+            .write_file(&resolved, data)
+            .map_err(|e| self.map_err(path, e))
+    }
+
+    fn append_file(&mut self, path: &Path, data: &[u8]) -> Result<(), Error> {
+        let resolved = self.resolve(path)?;
+        self.inner
+            .append_file(&resolved, data)
+            .map_err(|e| self.map_err(path, e))
+    }
+
+    fn create_dir(&mut self, path: &Path) -> Result<(), Error> {
+        let resolved = self.resolve_parent(path)?;
+        self.inner
+            .create_dir(&resolved)
+            .map_err(|e| self.map_err(path, e))
+    }
+
+    fn create_dir_all(&mut self, path: &Path) -> Result<(), Error> {
+        let resolved = self.resolve(path)?;
+        self.inner
+            .create_dir_all(&resolved)
+            .map_err(|e| self.map_err(path, e))
+    }
+
+    fn remove_file(&mut self, path: &Path) -> Result<(), Error> {
+        let resolved = self.resolve_parent(path)?;
+        self.inner
+            .remove_file(&resolved)
+            .map_err(|e| self.map_err(path, e))
+    }
+
+    fn remove_dir(&mut self, path: &Path) -> Result<(), Error> {
+        let resolved = self.resolve_parent(path)?;
+        self.inner
+            .remove_dir(&resolved)
+            .map_err(|e| self.map_err(path, e))
+    }
+
+    fn remove_dir_all(&mut self, path: &Path) -> Result<(), Error> {
+        let resolved = self.resolve_parent(path)?;
+        self.inner
+            .remove_dir_all(&resolved)
+            .map_err(|e| self.map_err(path, e))
+    }
+
+    fn rename(&mut self, from: &Path, to: &Path) -> Result<(), Error> {
+        let resolved_from = self.resolve_parent(from)?;
+        let resolved_to = self.resolve_parent(to)?;
+        self.inner
+            .rename(&resolved_from, &resolved_to)
+            .map_err(|e| self.map_err(from, e))
+    }
+
+    fn copy(&mut self, from: &Path, to: &Path) -> Result<u64, Error> {
+        let resolved_from = self.resolve(from)?;
+        let resolved_to = self.resolve(to)?;
+        self.inner
+            .copy(&resolved_from, &resolved_to)
+            .map_err(|e| self.map_err(from, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemFs;
+
+    #[test]
+    fn basics() {
+        let mut fs = SubFs::new(
+            MemFs::with_files(&[
+                ("/plugins/foo/config.toml", "/plugins/foo/config.toml"),
+                ("/plugins/foo/sub/data.toml", "/plugins/foo/sub/data.toml"),
+                ("/plugins/bar/secret.toml", "/plugins/bar/secret.toml"),
+            ]),
+            "/plugins/./foo",
+        )
+        .unwrap();
+        assert_eq!(fs.root(), Path::new("/plugins/foo"));
+
+        assert_eq!(
+            fs.read_to_string("/config.toml".as_ref()).unwrap(),
+            "/plugins/foo/config.toml"
+        );
+        assert_eq!(
+            fs.read_to_string("sub/../sub/data.toml".as_ref()).unwrap(),
+            "/plugins/foo/sub/data.toml"
+        );
+        assert_eq!(
+            fs.canonicalize_path("sub/./data.toml".as_ref()),
+            Ok("/sub/data.toml".into())
+        );
+
+        for path in ["../bar/secret.toml", "/sub/../../bar/secret.toml"] {
+            assert_eq!(
+                fs.read_to_string(path.as_ref()).unwrap_err(),
+                Error::PathOutsideBounds { path: path.into() },
+                "{path:?}"
+            );
+        }
+        assert_eq!(
+            fs.read_to_string("/missing.toml".as_ref()).unwrap_err(),
+            Error::not_found("/missing.toml")
+        );
+        assert_eq!(
+            fs.read_to_string("/config.toml/nope".as_ref()).unwrap_err(),
+            Error::NotADirectory {
+                path: "/config.toml".into(),
+                source: None
+            }
+        );
+
+        let entries: Vec<_> = fs
+            .read_dir("/".as_ref())
+            .unwrap()
+            .map(|e| {
+                let e = e.unwrap();
+                (e.path().to_path_buf(), e.is_dir())
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            [("/config.toml".into(), false), ("/sub".into(), true)]
+        );
+
+        assert_eq!(fs.chdir("sub"), Ok(Path::new("sub")));
+        assert_eq!(
+            fs.read_to_string("data.toml".as_ref()).unwrap(),
+            "/plugins/foo/sub/data.toml"
+        );
+        assert_eq!(
+            fs.read_to_string("../../bar/secret.toml".as_ref())
+                .unwrap_err(),
+            Error::PathOutsideBounds {
+                path: "../../bar/secret.toml".into()
+            }
+        );
+
+        assert_eq!(
+            SubFs::new(MemFs::with_files(&[("/file", "/file")]), "/file").unwrap_err(),
+            Error::NotADirectory {
+                path: "/file".into(),
+                source: None
+            }
+        );
+    }
+
+    #[test]
+    fn mutations() {
+        let mut fs = SubFs::new(
+            MemFs::with_files(&[("/plugins/foo/config.toml", "/plugins/foo/config.toml")]),
+            "/plugins/foo",
+        )
+        .unwrap();
+
+        fs.create_dir_all("/data/cache".as_ref()).unwrap();
+        fs.write_file("/data/cache/entry".as_ref(), b"cached")
+            .unwrap();
+        fs.rename("/config.toml".as_ref(), "/data/config.toml".as_ref())
+            .unwrap();
+        assert_eq!(
+            fs.copy("/data/cache/entry".as_ref(), "/data/copy".as_ref()),
+            Ok(6)
+        );
+        assert_eq!(
+            fs.write_file("../bar.toml".as_ref(), b"nope").unwrap_err(),
+            Error::PathOutsideBounds {
+                path: "../bar.toml".into()
+            }
+        );
+        assert_eq!(
+            fs.remove_file("/missing".as_ref()).unwrap_err(),
+            Error::not_found("/missing")
+        );
+        for path in ["/", "data/.."] {
+            let invalid = Error::InvalidPath {
+                path: path.into(),
+                source: None,
+            };
+            assert_eq!(fs.remove_dir_all(path.as_ref()), Err(invalid.clone()));
+            assert_eq!(fs.remove_dir(path.as_ref()), Err(invalid.clone()));
+            assert_eq!(fs.rename(path.as_ref(), "/moved".as_ref()), Err(invalid));
+        }
+        assert!(fs.read_dir("/".as_ref()).is_ok());
+
+        let inner = fs.into_inner();
+        assert_eq!(
+            inner
+                .read_to_string("/plugins/foo/data/cache/entry".as_ref())
+                .unwrap(),
+            "cached"
+        );
+        assert_eq!(
+            inner.read("/plugins/foo/data/copy".as_ref()).unwrap(),
+            b"cached"
+        );
+        assert!(inner
+            .metadata("/plugins/foo/data/config.toml".as_ref())
+            .unwrap()
+            .is_file());
+        assert!(inner.metadata("/bar.toml".as_ref()).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_stay_inside() {
+        use crate::RelFs;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let sub = tmp.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        std::fs::write(tmp.path().join("secret"), "secret").unwrap();
+        std::fs::write(sub.join("file"), "file").unwrap();
+        std::os::unix::fs::symlink("../secret", sub.join("escape")).unwrap();
+        std::os::unix::fs::symlink("file", sub.join("inside")).unwrap();
+
+        let fs = SubFs::new(RelFs::new(tmp.path()), "sub").unwrap();
+        assert_eq!(fs.root(), Path::new("/sub"));
+        assert_eq!(fs.read_to_string("/file".as_ref()).unwrap(), "file");
+        assert_eq!(fs.read_to_string("/inside".as_ref()).unwrap(), "file");
+        assert_eq!(fs.canonicalize_path("inside".as_ref()), Ok("/file".into()));
+        assert_eq!(
+            fs.read_to_string("/escape".as_ref()).unwrap_err(),
+            Error::PathOutsideBounds {
+                path: "/escape".into()
+            }
+        );
+        assert_eq!(
+            fs.metadata("/missing".as_ref())
+                .unwrap_err()
+                .without_source(),
+            Error::not_found("/missing")
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use std::{
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+};
+
+/// The maximum number of suggestions attached to an error
+const MAX_SUGGESTIONS: usize = 3;
+
+/// How close a candidate is to the name that was not found, lower is better
+fn score(name: &str, candidate: &str) -> Option<usize> {
+    if name == candidate {
+        return None;
+    }
+    if name.to_lowercase() == candidate.to_lowercase() {
+        return Some(0);
+    }
+
+    // NOTE(2026.10): A missing, extra or different extension, preferring matching case
+    let same_stem = |name: &str, candidate: &str| {
+        let name_stem = Path::new(name).file_stem().and_then(OsStr::to_str);
+        let candidate_stem = Path::new(candidate).file_stem().and_then(OsStr::to_str);
+        name_stem == Some(candidate) || candidate_stem == Some(name) || name_stem == candidate_stem
+    };
+    if same_stem(name, candidate) {
+        return Some(1);
+    }
+    if same_stem(&name.to_lowercase(), &candidate.to_lowercase()) {
+        return Some(2);
+    }
+
+    let distance = edit_distance(name, candidate);
+    let max_distance = (name.chars().count() / 3).max(1);
+    (distance <= max_distance).then_some(distance + 2)
+}
+
+/// The edit distance between `a` and `b` counted in characters, where
+/// swapping two adjacent characters counts as a single edit
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev2 = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+
+    for i in 0..a.len() {
+        cur[0] = i + 1;
+        for j in 0..b.len() {
+            let cost = usize::from(a[i] != b[j]);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+            if i > 0 && j > 0 && a[i] == b[j - 1] && a[i - 1] == b[j] {
+                cur[j + 1] = cur[j + 1].min(prev2[j - 1] + 1);
+            }
+        }
+        std::mem::swap(&mut prev2, &mut prev);
+        std::mem::swap(&mut prev, &mut cur);
+    }
+
+    prev[b.len()]
+}
+
+/// Pick the names in `candidates` that look like a typo of `name`, such as
+/// differences in case, a missing or extra extension, or a few misspelled
+/// characters. The best matches come first.
+pub(crate) fn similar_names(
+    name: &OsStr,
+    candidates: impl IntoIterator<Item = OsString>,
+) -> Vec<OsString> {
+    let name = name.to_string_lossy();
+    let mut scored: Vec<_> = candidates
+        .into_iter()
+        .filter_map(|candidate| Some((score(&name, &candidate.to_string_lossy())?, candidate)))
+        .collect();
+    scored.sort();
+    scored.truncate(MAX_SUGGESTIONS);
+    scored.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+/// Suggest paths similar to `path` for the first component that is missing,
+/// `list_dir` returns the names in a directory or `None` if it can't be read
+pub(crate) fn suggest_paths<I: IntoIterator<Item = OsString>>(
+    path: &Path,
+    mut list_dir: impl FnMut(&Path) -> Option<I>,
+) -> Vec<PathBuf> {
+    let mut cur = path;
+    while let (Some(parent), Some(name)) = (cur.parent(), cur.file_name()) {
+        if let Some(names) = list_dir(parent) {
+            let rest = path.strip_prefix(cur).unwrap_or(Path::new(""));
+            return similar_names(name, names)
+                .into_iter()
+                .map(|name| {
+                    let ret = parent.join(name);
+                    match rest.as_os_str().is_empty() {
+                        true => ret,
+                        false => ret.join(rest),
+                    }
+                })
+                .collect();
+        }
+        cur = parent;
+    }
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn similar(name: &str, candidates: &[&str]) -> Vec<OsString> {
+        similar_names(name.as_ref(), candidates.iter().map(OsString::from))
+    }
+
+    #[test]
+    fn ranking() {
+        assert_eq!(edit_distance("confg.toml", "config.toml"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("ect", "etc"), 1);
+
+        let names = [
+            "Config.toml",
+            "config",
+            "config.toml",
+            "config.yaml",
+            "other.toml",
+            "README.md",
+        ];
+        assert_eq!(
+            similar("confg.toml", &names),
+            ["config.toml", "Config.toml", "config.yaml"]
+        );
+        assert_eq!(
+            similar("CONFIG.TOML", &names),
+            ["Config.toml", "config.toml", "config"]
+        );
+        assert_eq!(
+            similar("config.json", &names),
+            ["config", "config.toml", "config.yaml"]
+        );
+        assert_eq!(
+            similar("config.toml", &names),
+            ["Config.toml", "config", "config.yaml"]
+        );
+        assert_eq!(similar("readme", &names), ["README.md"]);
+        assert!(similar("unrelated", &names).is_empty());
+    }
+}
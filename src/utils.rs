@@ -1,7 +1,45 @@
-use std::path::{Component, Path, PathBuf};
+use std::{
+    collections::VecDeque,
+    ffi::{OsStr, OsString},
+    path::{Component, Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use crate::{Error, LogixVfsDirEntry};
 
+/// The maximum number of symbolic links followed while resolving a single path,
+/// matching the limit used by Linux
+pub(crate) const MAX_SYMLINKS: usize = 40;
+
+/// A part of a path that is still to be resolved
+pub(crate) enum Part {
+    Parent,
+    Name(OsString),
+}
+
+/// Queue the parts of `path` in front of the pending parts, `orig` is the
+/// path reported in errors
+pub(crate) fn push_parts(
+    pending: &mut VecDeque<Part>,
+    path: &Path,
+    orig: &Path,
+) -> Result<(), Error> {
+    for cur in path.components().rev() {
+        match cur {
+            Component::Normal(name) => pending.push_front(Part::Name(name.to_owned())),
+            Component::ParentDir => pending.push_front(Part::Parent),
+            Component::CurDir | Component::RootDir => {}
+            Component::Prefix(_) => {
+                return Err(Error::InvalidPath {
+                    path: orig.to_path_buf(),
+                    source: None,
+                })
+            }
+        }
+    }
+    Ok(())
+}
+
 pub(crate) struct PathUtil<'a> {
     pub cur_dir: &'a Path,
     pub root: &'a Path,
@@ -39,12 +77,12 @@ impl<'a> PathUtil<'a> {
                     level -= 1;
                     ret.pop();
                 }
-                Component::Prefix(prefix) => {
+                Component::Prefix(_) => {
                     // NOTE(2024.02): Should never happen on platforms other than Windows
-                    return Err(Error::Other(format!(
-                        "Unknown prefix {:?}",
-                        prefix.as_os_str()
-                    )));
+                    return Err(Error::InvalidPath {
+                        path: path.to_path_buf(),
+                        source: None,
+                    });
                 }
             }
         }
@@ -53,6 +91,21 @@ impl<'a> PathUtil<'a> {
     }
 }
 
+/// Generate a hidden name next to `name` to write to before atomically
+/// renaming the file into place
+pub(crate) fn temp_name(name: &OsStr) -> OsString {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let mut ret = OsString::from(".");
+    ret.push(name);
+    ret.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    ret
+}
+
 impl LogixVfsDirEntry for PathBuf {
     fn path(&self) -> &Path {
         self
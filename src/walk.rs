@@ -0,0 +1,501 @@
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use crate::{Error, FileType, LogixVfs, LogixVfsDirEntry};
+
+/// Recursively walk `root` in `vfs`, see [Walk] for the available options
+pub fn walk<V: LogixVfs>(vfs: &V, root: impl AsRef<Path>) -> Walk<'_, V> {
+    Walk {
+        vfs,
+        root: root.as_ref().to_path_buf(),
+        min_depth: 0,
+        max_depth: usize::MAX,
+        order: Order::default(),
+        contents_first: false,
+        follow_symlinks: false,
+        on_error: OnError::default(),
+        prune: None,
+    }
+}
+
+/// The order of the entries within a directory
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Order {
+    /// Whatever order the file system returns them in
+    #[default]
+    Native,
+    /// Sorted by file name
+    Sorted,
+}
+
+/// What to do when reading a directory or an entry fails
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum OnError {
+    /// Yield the error and stop walking
+    #[default]
+    Abort,
+    /// Ignore the error and continue with the next entry
+    Skip,
+}
+
+/// An entry found while walking
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct WalkEntry {
+    path: PathBuf,
+    ty: FileType,
+    depth: usize,
+}
+
+impl WalkEntry {
+    /// The number of directories between the root and this entry, the root
+    /// itself has depth 0
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The type of the entry, when following symbolic links this is the
+    /// type of the target
+    pub fn file_type(&self) -> FileType {
+        self.ty
+    }
+}
+
+impl LogixVfsDirEntry for WalkEntry {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn is_dir(&self) -> bool {
+        self.ty == FileType::Dir
+    }
+
+    fn is_file(&self) -> bool {
+        self.ty == FileType::File
+    }
+
+    fn is_symlink(&self) -> bool {
+        self.ty == FileType::Symlink
+    }
+}
+
+type Prune<'a> = Box<dyn FnMut(&WalkEntry) -> bool + 'a>;
+
+/// A builder for a recursive walk, created by [walk]. The paths of the
+/// entries are the root as given joined with the names found below it.
+pub struct Walk<'a, V: LogixVfs> {
+    vfs: &'a V,
+    root: PathBuf,
+    min_depth: usize,
+    max_depth: usize,
+    order: Order,
+    contents_first: bool,
+    follow_symlinks: bool,
+    on_error: OnError,
+    prune: Option<Prune<'a>>,
+}
+
+impl<'a, V: LogixVfs> Walk<'a, V> {
+    /// Don't yield entries above this depth, they are still descended into
+    pub fn min_depth(mut self, depth: usize) -> Self {
+        self.min_depth = depth;
+        self
+    }
+
+    /// Don't descend below this depth
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    pub fn order(mut self, order: Order) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Yield the content of a directory before the directory itself
+    /// (post-order), the default is to yield the directory first (pre-order)
+    pub fn contents_first(mut self, yes: bool) -> Self {
+        self.contents_first = yes;
+        self
+    }
+
+    /// Descend into symbolic links pointing at directories, links leading
+    /// back to a directory being walked are reported as
+    /// [Error::TooManySymlinks]
+    pub fn follow_symlinks(mut self, yes: bool) -> Self {
+        self.follow_symlinks = yes;
+        self
+    }
+
+    pub fn on_error(mut self, on_error: OnError) -> Self {
+        self.on_error = on_error;
+        self
+    }
+
+    /// Skip entries for which `f` returns true, along with everything below
+    /// them
+    pub fn prune(mut self, f: impl FnMut(&WalkEntry) -> bool + 'a) -> Self {
+        self.prune = Some(Box::new(f));
+        self
+    }
+}
+
+impl<'a, V: LogixVfs> IntoIterator for Walk<'a, V> {
+    type Item = Result<WalkEntry, Error>;
+    type IntoIter = WalkIter<'a, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        WalkIter {
+            start: true,
+            stack: Vec::new(),
+            pending: None,
+            opts: self,
+        }
+    }
+}
+
+enum Listing<I> {
+    Native(I),
+    Sorted(std::vec::IntoIter<Result<(OsString, FileType), Error>>),
+}
+
+/// A directory that is being walked
+struct Frame<I> {
+    path: PathBuf,
+    canonical: PathBuf,
+    listing: Listing<I>,
+    /// The directory itself, kept until its content is done when yielding
+    /// contents first
+    entry: Option<WalkEntry>,
+}
+
+/// The iterator returned by [Walk::into_iter]
+pub struct WalkIter<'a, V: LogixVfs> {
+    opts: Walk<'a, V>,
+    start: bool,
+    stack: Vec<Frame<V::ReadDir>>,
+    /// An error to report after the entry that caused it
+    pending: Option<Error>,
+}
+
+impl<V: LogixVfs> WalkIter<'_, V> {
+    fn error(&mut self, e: Error) -> Option<Result<WalkEntry, Error>> {
+        match self.opts.on_error {
+            OnError::Abort => {
+                self.stack.clear();
+                self.pending = None;
+                Some(Err(e))
+            }
+            OnError::Skip => None,
+        }
+    }
+
+    fn read_listing(&self, path: &Path) -> Result<Listing<V::ReadDir>, Error> {
+        let it = self.opts.vfs.read_dir(path)?;
+        match self.opts.order {
+            Order::Native => Ok(Listing::Native(it)),
+            Order::Sorted => {
+                let mut entries = it.filter_map(Self::name_and_type).collect::<Vec<_>>();
+                entries.sort_by(|a, b| match (a, b) {
+                    (Ok((a, _)), Ok((b, _))) => a.cmp(b),
+                    (a, b) => a.is_ok().cmp(&b.is_ok()),
+                });
+                Ok(Listing::Sorted(entries.into_iter()))
+            }
+        }
+    }
+
+    fn name_and_type(
+        entry: Result<V::DirEntry, Error>,
+    ) -> Option<Result<(OsString, FileType), Error>> {
+        match entry {
+            Ok(entry) => {
+                let name = entry.path().file_name()?.to_owned();
+                Some(Ok((name, FileType::of(&entry))))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    fn next_in_frame(frame: &mut Frame<V::ReadDir>) -> Option<Result<(OsString, FileType), Error>> {
+        match &mut frame.listing {
+            Listing::Native(it) => it.find_map(Self::name_and_type),
+            Listing::Sorted(it) => it.next(),
+        }
+    }
+
+    /// Decide what to do with a newly found entry, returns the entry if it
+    /// should be yielded right away
+    fn visit(&mut self, mut entry: WalkEntry) -> Option<Result<WalkEntry, Error>> {
+        if let Some(prune) = &mut self.opts.prune {
+            if prune(&entry) {
+                return None;
+            }
+        }
+
+        if entry.ty == FileType::Symlink && self.opts.follow_symlinks {
+            match self.opts.vfs.metadata(&entry.path) {
+                Ok(meta) => entry.ty = meta.file_type(),
+                Err(e) => return self.error(e),
+            }
+        }
+
+        let visible = entry.depth >= self.opts.min_depth;
+
+        if entry.ty != FileType::Dir || entry.depth >= self.opts.max_depth {
+            return visible.then_some(Ok(entry));
+        }
+
+        let canonical = match self.opts.vfs.canonicalize_path(&entry.path) {
+            Ok(canonical) => canonical,
+            Err(e) => return self.error(e),
+        };
+        if self.stack.iter().any(|f| f.canonical == canonical) {
+            let path = entry.path;
+            return self.error(Error::TooManySymlinks { path, source: None });
+        }
+
+        let listing = match self.read_listing(&entry.path) {
+            Ok(listing) => listing,
+            Err(e) => {
+                // NOTE(2026.10): The directory itself is still reported before the error
+                if visible {
+                    if let OnError::Abort = self.opts.on_error {
+                        self.pending = Some(e);
+                    }
+                    return Some(Ok(entry));
+                }
+                return self.error(e);
+            }
+        };
+
+        let yield_now = visible && !self.opts.contents_first;
+        self.stack.push(Frame {
+            path: entry.path.clone(),
+            canonical,
+            listing,
+            entry: (visible && self.opts.contents_first).then(|| entry.clone()),
+        });
+        yield_now.then_some(Ok(entry))
+    }
+}
+
+impl<V: LogixVfs> Iterator for WalkIter<'_, V> {
+    type Item = Result<WalkEntry, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.pending.take() {
+            return self.error(e);
+        }
+
+        if std::mem::take(&mut self.start) {
+            let root = self.opts.root.clone();
+            let entry = match self.opts.vfs.metadata(&root) {
+                Ok(meta) => WalkEntry {
+                    path: root,
+                    ty: meta.file_type(),
+                    depth: 0,
+                },
+                Err(e) => return self.error(e),
+            };
+            if let Some(ret) = self.visit(entry) {
+                return Some(ret);
+            }
+        }
+
+        loop {
+            let depth = self.stack.len();
+            let frame = self.stack.last_mut()?;
+            match Self::next_in_frame(frame) {
+                Some(Ok((name, ty))) => {
+                    let entry = WalkEntry {
+                        path: frame.path.join(name),
+                        ty,
+                        depth,
+                    };
+                    if let Some(ret) = self.visit(entry) {
+                        return Some(ret);
+                    }
+                }
+                Some(Err(e)) => {
+                    if let Some(ret) = self.error(e) {
+                        return Some(ret);
+                    }
+                }
+                None => {
+                    if let Some(entry) = self.stack.pop().and_then(|f| f.entry) {
+                        return Some(Ok(entry));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemFs;
+
+    fn test_fs() -> MemFs {
+        MemFs::with_files(&[
+            ("/a/b/c/deep.txt", ""),
+            ("/a/one.txt", ""),
+            ("/b.txt", ""),
+            ("/c/two.txt", ""),
+        ])
+    }
+
+    fn paths<'a, V: LogixVfs>(walk: Walk<'a, V>) -> Vec<(String, usize)> {
+        walk.into_iter()
+            .map(|e| {
+                let e = e.unwrap();
+                (e.path().to_str().unwrap().to_owned(), e.depth())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn orders() {
+        let fs = test_fs();
+
+        assert_eq!(
+            paths(walk(&fs, "/").order(Order::Sorted)),
+            [
+                ("/".into(), 0),
+                ("/a".into(), 1),
+                ("/a/b".into(), 2),
+                ("/a/b/c".into(), 3),
+                ("/a/b/c/deep.txt".into(), 4),
+                ("/a/one.txt".into(), 2),
+                ("/b.txt".into(), 1),
+                ("/c".into(), 1),
+                ("/c/two.txt".into(), 2),
+            ]
+        );
+        assert_eq!(
+            paths(walk(&fs, "a").order(Order::Sorted).contents_first(true)),
+            [
+                ("a/b/c/deep.txt".into(), 3),
+                ("a/b/c".into(), 2),
+                ("a/b".into(), 1),
+                ("a/one.txt".into(), 1),
+                ("a".into(), 0),
+            ]
+        );
+        assert_eq!(paths(walk(&fs, "/b.txt")), [("/b.txt".into(), 0)],);
+    }
+
+    #[test]
+    fn depth_and_prune() {
+        let fs = test_fs();
+
+        assert_eq!(
+            paths(
+                walk(&fs, "/")
+                    .order(Order::Sorted)
+                    .min_depth(1)
+                    .max_depth(2)
+            ),
+            [
+                ("/a".into(), 1),
+                ("/a/b".into(), 2),
+                ("/a/one.txt".into(), 2),
+                ("/b.txt".into(), 1),
+                ("/c".into(), 1),
+                ("/c/two.txt".into(), 2),
+            ]
+        );
+        assert_eq!(
+            paths(
+                walk(&fs, "/")
+                    .order(Order::Sorted)
+                    .min_depth(2)
+                    .contents_first(true)
+                    .prune(|e| e.path().ends_with("b"))
+            ),
+            [("/a/one.txt".into(), 2), ("/c/two.txt".into(), 2)]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let fs = test_fs();
+
+        let res: Vec<_> = walk(&fs, "/missing").into_iter().collect();
+        assert_eq!(res, [Err(Error::not_found("/missing"))]);
+        assert_eq!(
+            walk(&fs, "/missing")
+                .on_error(OnError::Skip)
+                .into_iter()
+                .count(),
+            0
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks() {
+        use crate::RelFs;
+
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("dir/sub")).unwrap();
+        std::fs::write(tmp.path().join("dir/sub/file"), "").unwrap();
+        std::os::unix::fs::symlink("sub", tmp.path().join("dir/link")).unwrap();
+        std::os::unix::fs::symlink("..", tmp.path().join("dir/sub/up")).unwrap();
+        let fs = RelFs::new(tmp.path());
+
+        let res: Vec<_> = walk(&fs, "dir")
+            .order(Order::Sorted)
+            .into_iter()
+            .map(|e| {
+                let e = e.unwrap();
+                (e.path().to_str().unwrap().to_owned(), e.file_type())
+            })
+            .collect();
+        assert_eq!(
+            res,
+            [
+                ("dir".into(), FileType::Dir),
+                ("dir/link".into(), FileType::Symlink),
+                ("dir/sub".into(), FileType::Dir),
+                ("dir/sub/file".into(), FileType::File),
+                ("dir/sub/up".into(), FileType::Symlink),
+            ]
+        );
+
+        let res: Vec<_> = walk(&fs, "dir")
+            .order(Order::Sorted)
+            .follow_symlinks(true)
+            .on_error(OnError::Skip)
+            .into_iter()
+            .map(|e| e.unwrap().path().to_str().unwrap().to_owned())
+            .collect();
+        assert_eq!(
+            res,
+            [
+                "dir",
+                "dir/link",
+                "dir/link/file",
+                "dir/sub",
+                "dir/sub/file"
+            ]
+        );
+
+        let err = walk(&fs, "dir")
+            .order(Order::Sorted)
+            .follow_symlinks(true)
+            .into_iter()
+            .find_map(|e| e.err())
+            .unwrap();
+        assert_eq!(
+            err,
+            Error::TooManySymlinks {
+                path: "dir/link/up".into(),
+                source: None
+            }
+        );
+    }
+}